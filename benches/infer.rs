@@ -0,0 +1,205 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use jtd_infer::{HintSet, Hints, Inferrer, NumType};
+use serde_json::{json, Map, Value};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// An allocator that keeps track of the peak number of bytes allocated, so
+/// that the enum collection benchmark can report on memory usage.
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAlloc = PeakAlloc;
+
+fn no_hints<'a>() -> Hints<'a> {
+    Hints::new(
+        NumType::Uint8,
+        HintSet::new(vec![]),
+        HintSet::new(vec![]),
+        HintSet::new(vec![]),
+    )
+}
+
+fn infer_all(hints: Hints, values: Vec<Value>) {
+    let mut inferrer = Inferrer::new(hints);
+    for value in values {
+        inferrer = inferrer.infer(value);
+    }
+
+    black_box(inferrer.into_schema().unwrap());
+}
+
+fn deep_object(depth: usize) -> Value {
+    let mut value = json!({ "leaf": 1 });
+    for i in 0..depth {
+        value = json!({ "name": format!("level {}", i), "child": value });
+    }
+
+    value
+}
+
+fn wide_object(width: usize, seed: usize) -> Value {
+    let mut obj = Map::new();
+    for i in 0..width {
+        obj.insert(format!("key{}", i), json!(i + seed));
+    }
+
+    Value::Object(obj)
+}
+
+fn bench_deep_objects(c: &mut Criterion) {
+    c.bench_function("deep objects", |b| {
+        b.iter_batched(
+            || (0..100).map(|_| deep_object(100)).collect(),
+            |values| infer_all(no_hints(), values),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_wide_objects(c: &mut Criterion) {
+    c.bench_function("wide objects", |b| {
+        b.iter_batched(
+            || (0..100).map(|i| wide_object(5000, i)).collect(),
+            |values| infer_all(no_hints(), values),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_homogeneous_array(c: &mut Criterion) {
+    c.bench_function("homogeneous array", |b| {
+        b.iter_batched(
+            || {
+                let elements = (0..100_000)
+                    .map(|i| json!({ "id": i, "name": "xxx", "ok": i % 2 == 0 }))
+                    .collect();
+
+                vec![Value::Array(elements)]
+            },
+            |values| infer_all(no_hints(), values),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn string_column(rows: usize) -> Vec<Value> {
+    (0..rows)
+        .map(|i| json!({ "column": format!("value {}", i) }))
+        .collect()
+}
+
+fn bench_high_cardinality_strings(c: &mut Criterion) {
+    let enum_path = vec!["column".to_string()];
+
+    c.bench_function("high cardinality strings", |b| {
+        b.iter_batched(
+            || string_column(100_000),
+            |values| infer_all(no_hints(), values),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("high cardinality enum", |b| {
+        b.iter_batched(
+            || string_column(100_000),
+            |values| {
+                let hints = Hints::new(
+                    NumType::Uint8,
+                    HintSet::new(vec![&enum_path]),
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![]),
+                );
+
+                infer_all(hints, values)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("high cardinality enum fallback", |b| {
+        b.iter_batched(
+            || string_column(100_000),
+            |values| {
+                let hints = Hints::new(
+                    NumType::Uint8,
+                    HintSet::new(vec![&enum_path]),
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![]),
+                )
+                .with_max_enum_size(255);
+
+                infer_all(hints, values)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Reports how much memory enum collection holds on to over a million-row,
+/// all-distinct string column.
+///
+/// Rows are generated lazily, so the reported peak is (almost entirely) made
+/// up of inference state rather than the input itself.
+fn bench_enum_memory(c: &mut Criterion) {
+    let enum_path = vec!["column".to_string()];
+
+    let run = || {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        let mut inferrer = Inferrer::new(hints);
+        for i in 0..1_000_000 {
+            inferrer = inferrer.infer(json!({ "column": format!("value {}", i) }));
+        }
+
+        black_box(inferrer.into_schema().unwrap());
+    };
+
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    run();
+    println!(
+        "enum collection over 1,000,000 distinct strings: peak {} bytes",
+        PEAK.load(Ordering::Relaxed) - baseline
+    );
+
+    let mut group = c.benchmark_group("enum memory");
+    group.sample_size(10);
+    group.bench_function("million row enum column", |b| b.iter(run));
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_deep_objects,
+    bench_wide_objects,
+    bench_homogeneous_array,
+    bench_high_cardinality_strings,
+    bench_enum_memory,
+);
+criterion_main!(benches);
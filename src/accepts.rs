@@ -0,0 +1,51 @@
+use jtd::{Schema, ValidateOptions};
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// Whether `schema` accepts `value`, according to JSON Typedef's validation
+/// rules.
+///
+/// With the default hints, [`Inferrer::into_schema`][`crate::Inferrer::into_schema`]
+/// promises that the schema it returns accepts every input it was given,
+/// though some hints give that up on purpose. This lets that promise, or any
+/// other document, be checked in your own tests:
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::{schema_accepts, Hints, Inferrer};
+///
+/// let inputs = vec![json!({ "name": "Alice" }), json!({ "name": "Bob", "age": 42 })];
+/// let schema = Inferrer::new(Hints::default())
+///     .infer(inputs[0].clone())
+///     .infer(inputs[1].clone())
+///     .into_schema()
+///     .unwrap();
+///
+/// assert!(inputs.iter().all(|input| schema_accepts(&schema, input)));
+/// assert!(!schema_accepts(&schema, &json!({ "age": 42 })));
+/// ```
+///
+/// This is a thin wrapper around [`jtd::validate`], for when only a yes or no
+/// is needed. `schema` should be valid, as checked by [`Schema::validate`],
+/// which every schema from this crate is.
+pub fn schema_accepts(schema: &Schema, value: &Value) -> bool {
+    first_rejection(schema, value).is_none()
+}
+
+/// Where `schema` first rejects `value`, as the path to the rejected part of
+/// `value` and the path to the part of `schema` that rejected it.
+pub(crate) fn first_rejection(
+    schema: &Schema,
+    value: &Value,
+) -> Option<(Vec<String>, Vec<String>)> {
+    // Stopping at the first error is enough to say what went wrong, and
+    // without a maximum depth validation can't fail.
+    let options = ValidateOptions::new().with_max_errors(1);
+    let errors = jtd::validate(schema, value, options)
+        .expect("validation without a maximum depth never fails");
+
+    errors.into_iter().next().map(|error| {
+        let to_owned = |path: Vec<Cow<str>>| path.into_iter().map(Cow::into_owned).collect();
+        (to_owned(error.instance_path), to_owned(error.schema_path))
+    })
+}
@@ -0,0 +1,402 @@
+use crate::error::{to_json_pointer, type_name};
+use crate::hints::{
+    BigIntegers, BooleanStrings, DateDetection, EmptyFallback, HintMap, HintSet, Hints,
+    MixedScalars, NullableFallback,
+};
+use crate::inferred_number::NumType;
+use jtd::Type;
+use serde::{Deserialize, Serialize};
+
+/// A configuration for [`Hints`] that can be stored in, and loaded from, a
+/// file.
+///
+/// Every field has the same meaning as the corresponding part of [`Hints`].
+/// Paths are written as JSON Pointers, and every field is optional:
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::{Config, Inferrer};
+///
+/// let config: Config = serde_json::from_value(json!({
+///     "defaultNumberType": "float64",
+///     "enumHints": ["/status"],
+///     "typeHints": { "/id": "string" },
+/// }))
+/// .unwrap();
+///
+/// let inferrer = Inferrer::new(config.hints())
+///     .infer(json!({ "status": "ok", "id": "2020-01-01T00:00:00Z", "n": 1 }));
+///
+/// assert_eq!(
+///     json!({
+///         "properties": {
+///             "id": { "type": "string" },
+///             "n": { "type": "float64" },
+///             "status": { "enum": ["ok"] },
+///         },
+///     }),
+///     inferrer.into_json().unwrap(),
+/// )
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Config {
+    pub default_number_type: NumType,
+
+    #[serde(with = "pointers")]
+    pub enum_hints: Vec<Vec<String>>,
+
+    #[serde(with = "pointers")]
+    pub values_hints: Vec<Vec<String>>,
+
+    #[serde(with = "pointers")]
+    pub discriminator_hints: Vec<Vec<String>>,
+
+    #[serde(with = "pointers")]
+    pub open_object_hints: Vec<Vec<String>>,
+
+    #[serde(with = "pointers")]
+    pub any_hints: Vec<Vec<String>>,
+
+    /// Type hints, written as a map from JSON Pointers to the names of JSON
+    /// Typedef types, such as `"string"` or `"uint8"`.
+    #[serde(with = "type_hints")]
+    pub type_hints: Vec<(Vec<String>, Type)>,
+
+    /// Number type hints, written as a map from JSON Pointers to number types.
+    #[serde(with = "number_type_hints")]
+    pub number_type_hints: Vec<(Vec<String>, NumType)>,
+
+    /// Subtree number type hints, written in the same way as number type
+    /// hints. See [`Hints::with_subtree_number_type_hints`].
+    #[serde(with = "number_type_hints")]
+    pub subtree_number_type_hints: Vec<(Vec<String>, NumType)>,
+
+    pub max_enum_size: Option<usize>,
+
+    /// See [`Hints::with_integer_enums`].
+    pub integer_enums: Option<usize>,
+
+    /// See [`Hints::with_element_discriminators`].
+    pub element_discriminators: Option<usize>,
+
+    /// See [`Hints::with_values_collapse`].
+    pub values_collapse: Option<usize>,
+
+    /// See [`Hints::with_min_required_samples`].
+    pub min_required_samples: usize,
+
+    pub absent_as_nullable: bool,
+    pub open_objects: bool,
+    pub histograms: bool,
+    pub tuples: bool,
+    pub key_descriptions: bool,
+    pub date_detection: DateDetection,
+    pub email_detection: bool,
+
+    /// The minimum length of strings to detect content encodings in. See
+    /// [`Hints::with_content_encodings`].
+    pub content_encodings: Option<usize>,
+
+    /// See [`Hints::with_content_encoding_ratio`].
+    pub content_encoding_ratio: Option<f64>,
+
+    /// See [`Hints::with_format_sample_size`].
+    pub format_sample_size: Option<usize>,
+
+    pub boolean_strings: Option<BooleanStrings>,
+    pub mixed_scalars: MixedScalars,
+    pub case_insensitive_keys: bool,
+    pub empty_fallback: EmptyFallback,
+
+    /// If set, this is the same as an `empty_fallback` of
+    /// [`EmptyFallback::Number`], and takes precedence over it.
+    pub unknown_as_number: bool,
+
+    pub big_integers: BigIntegers,
+    pub nested_values: bool,
+    pub emit_nullable: bool,
+    pub nullable_fallback: NullableFallback,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            default_number_type: NumType::default(),
+            enum_hints: vec![],
+            values_hints: vec![],
+            discriminator_hints: vec![],
+            open_object_hints: vec![],
+            any_hints: vec![],
+            type_hints: vec![],
+            number_type_hints: vec![],
+            subtree_number_type_hints: vec![],
+            max_enum_size: None,
+            integer_enums: None,
+            element_discriminators: None,
+            values_collapse: None,
+            min_required_samples: 0,
+            absent_as_nullable: false,
+            open_objects: false,
+            histograms: false,
+            tuples: false,
+            key_descriptions: false,
+            date_detection: DateDetection::default(),
+            email_detection: false,
+            content_encodings: None,
+            content_encoding_ratio: None,
+            format_sample_size: None,
+            boolean_strings: None,
+            mixed_scalars: MixedScalars::default(),
+            case_insensitive_keys: false,
+            empty_fallback: EmptyFallback::default(),
+            unknown_as_number: false,
+            big_integers: BigIntegers::default(),
+            nested_values: false,
+            emit_nullable: true,
+            nullable_fallback: NullableFallback::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Constructs the [`Hints`] that this configuration describes.
+    pub fn hints(&self) -> Hints<'_> {
+        let hints = Hints::new(
+            self.default_number_type,
+            HintSet::from_paths(&self.enum_hints),
+            HintSet::from_paths(&self.values_hints),
+            HintSet::from_paths(&self.discriminator_hints),
+        )
+        .with_open_objects(self.open_objects)
+        .with_open_object_paths(HintSet::from_paths(&self.open_object_hints))
+        .with_any_paths(HintSet::from_paths(&self.any_hints))
+        .with_type_hints(HintMap::from_paths(&self.type_hints))
+        .with_number_type_hints(HintMap::from_paths(&self.number_type_hints))
+        .with_subtree_number_type_hints(HintMap::from_paths(&self.subtree_number_type_hints))
+        .with_histograms(self.histograms)
+        .with_tuples(self.tuples)
+        .with_key_descriptions(self.key_descriptions)
+        .with_date_detection(self.date_detection)
+        .with_email_detection(self.email_detection)
+        .with_mixed_scalars(self.mixed_scalars)
+        .with_case_insensitive_keys(self.case_insensitive_keys)
+        .with_empty_fallback(self.empty_fallback)
+        .with_big_integers(self.big_integers)
+        .with_nested_values(self.nested_values)
+        .with_emit_nullable(self.emit_nullable)
+        .with_nullable_fallback(self.nullable_fallback)
+        .with_min_required_samples(self.min_required_samples)
+        .with_absent_as_nullable(self.absent_as_nullable);
+
+        let hints = match self.unknown_as_number {
+            true => hints.with_unknown_as_number(true),
+            false => hints,
+        };
+
+        let hints = match self.max_enum_size {
+            Some(max_enum_size) => hints.with_max_enum_size(max_enum_size),
+            None => hints,
+        };
+
+        let hints = match self.integer_enums {
+            Some(max_size) => hints.with_integer_enums(max_size),
+            None => hints,
+        };
+
+        let hints = match self.content_encodings {
+            Some(min_length) => hints.with_content_encodings(min_length),
+            None => hints,
+        };
+
+        let hints = match self.content_encoding_ratio {
+            Some(min_ratio) => hints.with_content_encoding_ratio(min_ratio),
+            None => hints,
+        };
+
+        let hints = match self.format_sample_size {
+            Some(format_sample_size) => hints.with_format_sample_size(format_sample_size),
+            None => hints,
+        };
+
+        let hints = match self.element_discriminators {
+            Some(max_tags) => hints.with_element_discriminators(max_tags),
+            None => hints,
+        };
+
+        let hints = match self.values_collapse {
+            Some(min_optional) => hints.with_values_collapse(min_optional),
+            None => hints,
+        };
+
+        match &self.boolean_strings {
+            Some(boolean_strings) => hints.with_boolean_strings(boolean_strings),
+            None => hints,
+        }
+    }
+}
+
+/// Parses the name of a JSON Typedef type, as it appears in a schema.
+pub(crate) fn parse_type_name(name: &str) -> Option<Type> {
+    match name {
+        "boolean" => Some(Type::Boolean),
+        "string" => Some(Type::String),
+        "timestamp" => Some(Type::Timestamp),
+        "int8" => Some(Type::Int8),
+        "uint8" => Some(Type::Uint8),
+        "int16" => Some(Type::Int16),
+        "uint16" => Some(Type::Uint16),
+        "int32" => Some(Type::Int32),
+        "uint32" => Some(Type::Uint32),
+        "float32" => Some(Type::Float32),
+        "float64" => Some(Type::Float64),
+        _ => None,
+    }
+}
+
+/// (De)serializes a list of paths as a list of JSON Pointers.
+mod pointers {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        paths: &[Vec<String>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(paths.iter().map(|path| to_json_pointer(path)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<String>>, D::Error> {
+        let pointers = Vec::<String>::deserialize(deserializer)?;
+        Ok(pointers
+            .iter()
+            .map(|pointer| crate::parse_json_pointer(pointer))
+            .collect())
+    }
+}
+
+/// (De)serializes type hints as a map from JSON Pointers to type names.
+mod type_hints {
+    use super::*;
+    use serde::de::Error;
+    use serde::{Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        type_hints: &[(Vec<String>, Type)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            type_hints
+                .iter()
+                .map(|(path, type_)| (to_json_pointer(path), type_name(type_))),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Vec<String>, Type)>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(pointer, name)| match parse_type_name(&name) {
+                Some(type_) => Ok((crate::parse_json_pointer(&pointer), type_)),
+                None => Err(D::Error::custom(format!("unknown type: {:?}", name))),
+            })
+            .collect()
+    }
+}
+
+/// (De)serializes number type hints as a map from JSON Pointers to number
+/// types.
+mod number_type_hints {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        num_type_hints: &[(Vec<String>, NumType)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            num_type_hints
+                .iter()
+                .map(|(path, num_type)| (to_json_pointer(path), num_type)),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Vec<String>, NumType)>, D::Error> {
+        Ok(BTreeMap::<String, NumType>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(pointer, num_type)| (crate::parse_json_pointer(&pointer), num_type))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trip() {
+        let config = json!({
+            "defaultNumberType": "int32",
+            "enumHints": ["/a/-/b"],
+            "valuesHints": [""],
+            "discriminatorHints": [],
+            "openObjectHints": ["/c"],
+            "anyHints": ["/f/-"],
+            "typeHints": { "/d": "timestamp" },
+            "numberTypeHints": { "/e": "float32" },
+            "subtreeNumberTypeHints": { "/g": "uint32" },
+            "maxEnumSize": 10,
+            "integerEnums": 5,
+            "elementDiscriminators": 8,
+            "valuesCollapse": 20,
+            "minRequiredSamples": 3,
+            "absentAsNullable": true,
+            "openObjects": false,
+            "histograms": true,
+            "tuples": true,
+            "keyDescriptions": true,
+            "dateDetection": "strict",
+            "emailDetection": true,
+            "contentEncodings": 16,
+            "contentEncodingRatio": 0.9,
+            "formatSampleSize": 64,
+            "booleanStrings": { "tokens": ["y", "n"], "ignoreCase": true },
+            "mixedScalars": "string",
+            "caseInsensitiveKeys": false,
+            "emptyFallback": "unknown",
+            "unknownAsNumber": true,
+            "bigIntegers": "string",
+            "nestedValues": true,
+            "emitNullable": false,
+            "nullableFallback": "base",
+        });
+
+        let parsed: Config = serde_json::from_value(config.clone()).unwrap();
+        assert_eq!(vec![vec!["a", "-", "b"]], parsed.enum_hints);
+        assert_eq!(vec![Vec::<String>::new()], parsed.values_hints);
+        assert_eq!(
+            vec![(vec!["d".to_owned()], Type::Timestamp)],
+            parsed.type_hints
+        );
+        assert_eq!(config, serde_json::to_value(&parsed).unwrap());
+
+        // Fields that are left out take their defaults.
+        let parsed: Config = serde_json::from_value(json!({})).unwrap();
+        assert!(parsed.emit_nullable);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(serde_json::from_value::<Config>(json!({ "typeHints": { "": "date" } })).is_err());
+        assert!(serde_json::from_value::<Config>(json!({ "enumHint": ["/a"] })).is_err());
+        assert!(serde_json::from_value::<Config>(json!({ "defaultNumberType": "Uint8" })).is_err());
+    }
+}
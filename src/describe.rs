@@ -0,0 +1,179 @@
+use crate::stamp::metadata_mut;
+use jtd::Schema;
+use serde_json::Value;
+
+/// Gives each property of `schema`, if it's of the properties form, a
+/// `description` in its `metadata` made from its key, as by [`describe_key`].
+///
+/// A property that already has a description keeps it.
+pub(crate) fn describe_properties(schema: &mut Schema) {
+    if let Schema::Properties {
+        properties,
+        optional_properties,
+        ..
+    } = schema
+    {
+        for (key, sub_schema) in properties.iter_mut().chain(optional_properties) {
+            if let Some(description) = describe_key(key) {
+                metadata_mut(sub_schema)
+                    .entry("description".to_owned())
+                    .or_insert(Value::String(description));
+            }
+        }
+    }
+}
+
+/// A human-readable description of a property from its key alone, such as
+/// "Created at" for `createdAt` or `created_at`, or `None` if the key has no
+/// words in it.
+///
+/// Words are split at underscores, dashes, dots, and spaces, and where the case
+/// changes from lower to upper. A run of capitals is kept together as an
+/// acronym, as in "User ID" for `userID`. The first word is capitalized, and
+/// the rest are in lowercase, apart from acronyms.
+pub(crate) fn describe_key(key: &str) -> Option<String> {
+    let words = split_words(key);
+    let (first, rest) = words.split_first()?;
+
+    let mut description = capitalize(first);
+    for word in rest {
+        description.push(' ');
+        if is_acronym(word) {
+            description.push_str(word);
+        } else {
+            description.push_str(&word.to_lowercase());
+        }
+    }
+
+    Some(description)
+}
+
+fn split_words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        // A capital starts a new word after a lowercase letter or a digit,
+        // as in "createdAt", or at the end of an acronym, as in the "S" of
+        // "HTTPServer".
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && match prev {
+                Some(prev) if prev.is_lowercase() || prev.is_numeric() => true,
+                Some(prev) if prev.is_uppercase() => next.is_some_and(|next| next.is_lowercase()),
+                _ => false,
+            };
+
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    if is_acronym(word) {
+        return word.to_owned();
+    }
+
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether `word` is more than one letter, all of them capitals, like "ID".
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() > 1 && !word.chars().any(char::is_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn describe_key() {
+        let cases = [
+            ("createdAt", Some("Created at")),
+            ("created_at", Some("Created at")),
+            ("CreatedAt", Some("Created at")),
+            ("created-at", Some("Created at")),
+            ("CREATED_AT", Some("CREATED AT")),
+            ("userID", Some("User ID")),
+            ("HTTPServer", Some("HTTP server")),
+            ("address2", Some("Address2")),
+            ("line2Text", Some("Line2 text")),
+            ("id", Some("Id")),
+            ("x", Some("X")),
+            ("__meta__", Some("Meta")),
+            ("", None),
+            ("_-", None),
+        ];
+
+        for (key, description) in cases {
+            assert_eq!(
+                description.map(str::to_owned),
+                super::describe_key(key),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn describe_properties() {
+        let mut schema = Schema::from_serde_schema(
+            serde_json::from_value(json!({
+                "properties": {
+                    "firstName": { "type": "string" },
+                    "lastName": {
+                        "type": "string",
+                        "metadata": { "description": "Family name" },
+                    },
+                },
+                "optionalProperties": { "_": {} },
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        super::describe_properties(&mut schema);
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "firstName": {
+                        "type": "string",
+                        "metadata": { "description": "First name" },
+                    },
+                    "lastName": {
+                        "type": "string",
+                        "metadata": { "description": "Family name" },
+                    },
+                },
+                "optionalProperties": { "_": {} },
+            }),
+            serde_json::to_value(schema.into_serde_schema()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::hints::WILDCARD;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A JSON value, along with where it repeated a key within the same object.
+///
+/// JSON allows an object to have the same key more than once. Parsing into a
+/// [`Value`] keeps only the last value of such a key, which silently hides the
+/// others. Parsing into a [`CheckedValue`] keeps the last value too, so that
+/// `value` is what a [`Value`] would have been, but also records the repeats.
+///
+/// Give the result to [`Inferrer::infer_checked`][`crate::Inferrer::infer_checked`]
+/// to have the repeats reported as [`Warning::DuplicateKey`][`crate::Warning::DuplicateKey`].
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::CheckedValue;
+///
+/// let checked: CheckedValue =
+///     serde_json::from_str(r#"{ "a": [{ "b": 1, "b": "x" }] }"#).unwrap();
+///
+/// assert_eq!(json!({ "a": [{ "b": "x" }] }), checked.value);
+/// assert_eq!(vec![vec!["a", "-", "b"]], checked.duplicate_keys);
+/// ```
+///
+/// Since it's a [`Deserialize`] type, a stream of them can be parsed the same
+/// way as a stream of [`Value`]s, with
+/// [`serde_json::StreamDeserializer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckedValue {
+    pub value: Value,
+
+    /// The path to each key that was repeated, ending in the key itself, in
+    /// the same form as the paths given to [`Hints`][`crate::Hints`]. A key is
+    /// listed once for every time it was repeated.
+    pub duplicate_keys: Vec<Vec<String>>,
+}
+
+/// A value that's known to have no repeated keys, such as one that was already
+/// parsed.
+impl From<Value> for CheckedValue {
+    fn from(value: Value) -> Self {
+        Self {
+            value,
+            duplicate_keys: vec![],
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CheckedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut duplicate_keys = vec![];
+        let value = Checker {
+            path: &mut vec![],
+            duplicate_keys: &mut duplicate_keys,
+        }
+        .deserialize(deserializer)?;
+
+        Ok(Self {
+            value,
+            duplicate_keys,
+        })
+    }
+}
+
+/// Deserializes a [`Value`] found at `path`, recording any repeated keys
+/// within it in `duplicate_keys`.
+struct Checker<'a> {
+    path: &'a mut Vec<String>,
+    duplicate_keys: &'a mut Vec<Vec<String>>,
+}
+
+impl Checker<'_> {
+    fn nested(&mut self) -> Checker<'_> {
+        Checker {
+            path: self.path,
+            duplicate_keys: self.duplicate_keys,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Checker<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Checker<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Value, E> {
+        Ok(Value::from(n))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<Value, E> {
+        Ok(Value::from(n))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<Value, E> {
+        Ok(Value::from(n))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::String(s.to_owned()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Value, E> {
+        Ok(Value::String(s))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = vec![];
+
+        self.path.push(WILDCARD.to_owned());
+        while let Some(value) = seq.next_element_seed(self.nested())? {
+            values.push(value);
+        }
+        self.path.pop();
+
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Value, A::Error> {
+        let mut values = Map::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            self.path.push(key);
+            let value = map.next_value_seed(self.nested())?;
+
+            if values.contains_key(self.path.last().unwrap()) {
+                self.duplicate_keys.push(self.path.clone());
+            }
+
+            let key = self.path.pop().unwrap();
+            values.insert(key, value);
+        }
+
+        Ok(Value::Object(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn duplicate_keys() {
+        let checked: CheckedValue = serde_json::from_str(
+            r#"{ "a": 1, "b": { "c": null, "c": [{ "d": 2, "d": 3, "d": true }] }, "a": "x" }"#,
+        )
+        .unwrap();
+
+        // The last value of each key wins, just as with Value.
+        assert_eq!(
+            json!({ "a": "x", "b": { "c": [{ "d": true }] } }),
+            checked.value
+        );
+        assert_eq!(
+            vec![
+                vec!["b", "c", "-", "d"],
+                vec!["b", "c", "-", "d"],
+                vec!["b", "c"],
+                vec!["a"],
+            ],
+            checked.duplicate_keys
+        );
+
+        let input = r#"[1.5, -2, 18446744073709551615, "s", false, null, {}]"#;
+        let checked: CheckedValue = serde_json::from_str(input).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(input).unwrap(), checked.value);
+        assert!(checked.duplicate_keys.is_empty());
+    }
+}
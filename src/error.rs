@@ -0,0 +1,254 @@
+use jtd::Type;
+use std::fmt;
+
+/// An error that can occur when converting an inference into a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InferError {
+    /// A type hint was given for a part of the input whose examples can't all
+    /// be described by the hinted type.
+    ///
+    /// `path` is the path to that part of the input, in the same form as the
+    /// paths given to [`Hints`][`crate::Hints`]. `inferred` is a short
+    /// description of what was inferred from the examples there instead.
+    TypeHintMismatch {
+        path: Vec<String>,
+        type_: Type,
+        inferred: &'static str,
+    },
+
+    /// An enum hint was given for a part of the input whose examples were
+    /// neither strings nor numbers, which can't be an enum.
+    ///
+    /// `path` and `inferred` are as in [`InferError::TypeHintMismatch`].
+    EnumHintMismatch {
+        path: Vec<String>,
+        inferred: &'static str,
+    },
+
+    /// A discriminator hint pointed to a part of the input whose examples were
+    /// objects, rather than to a property of them, which would be the tag. For
+    /// a discriminator at the root, the hint is the path to the tag, such as
+    /// `"/type"`, and not `""`.
+    ///
+    /// `path` is as in [`InferError::TypeHintMismatch`].
+    DiscriminatorHintWithoutTag { path: Vec<String> },
+
+    /// A discriminator hint pointed to a tag that some of the objects didn't
+    /// have, or had as something other than a string. JSON Typedef requires
+    /// every object to have its tag, as a string.
+    ///
+    /// `path` is the path to the objects, as in
+    /// [`InferError::TypeHintMismatch`], and `discriminator` is the name of the
+    /// tag. `untagged` is how many of the objects were without it.
+    DiscriminatorTagMissing {
+        path: Vec<String>,
+        discriminator: String,
+        untagged: usize,
+    },
+
+    /// The inferred schema isn't a valid JSON Typedef schema.
+    ///
+    /// This should never happen, and indicates a bug in this crate. It's
+    /// reported rather than ignored so that an invalid schema is never handed
+    /// back.
+    InvalidSchema(jtd::SchemaValidateError),
+
+    /// The inferred schema rejects one of the examples it was inferred from,
+    /// as found by [`Inferrer::into_validated_schema`][`crate::Inferrer::into_validated_schema`].
+    ///
+    /// With the default hints, this indicates a bug in this crate, but some
+    /// hints infer schemas that reject examples on purpose, as listed there.
+    /// `index` is the position of the example among those retained, and
+    /// `instance_path` and `schema_path` are where the schema first rejected
+    /// it.
+    SampleRejected {
+        index: usize,
+        instance_path: Vec<String>,
+        schema_path: Vec<String>,
+    },
+
+    /// The input given to [`Inferrer::infer_json_stream`][`crate::Inferrer::infer_json_stream`]
+    /// couldn't be parsed as a stream of JSON values.
+    ///
+    /// This holds the message of the underlying [`serde_json::Error`], which
+    /// includes the line and column where parsing failed.
+    InvalidJson(String),
+
+    /// The name given to [`name_root`][`crate::name_root`] is already the name
+    /// of one of the schema's definitions.
+    DuplicateDefinition(String),
+
+    /// A string parsed as a [`NumType`][`crate::NumType`] wasn't the name of
+    /// one, such as `"uint8"`. This holds the string.
+    UnknownNumType(String),
+}
+
+impl InferError {
+    /// Prefixes the path of the error with `key`, as it propagates up out of a
+    /// sub-schema.
+    pub(crate) fn within(self, key: &str) -> Self {
+        match self {
+            InferError::TypeHintMismatch {
+                mut path,
+                type_,
+                inferred,
+            } => {
+                path.insert(0, key.to_owned());
+                InferError::TypeHintMismatch {
+                    path,
+                    type_,
+                    inferred,
+                }
+            }
+            InferError::EnumHintMismatch { mut path, inferred } => {
+                path.insert(0, key.to_owned());
+                InferError::EnumHintMismatch { path, inferred }
+            }
+            InferError::DiscriminatorHintWithoutTag { mut path } => {
+                path.insert(0, key.to_owned());
+                InferError::DiscriminatorHintWithoutTag { path }
+            }
+            InferError::DiscriminatorTagMissing {
+                mut path,
+                discriminator,
+                untagged,
+            } => {
+                path.insert(0, key.to_owned());
+                InferError::DiscriminatorTagMissing {
+                    path,
+                    discriminator,
+                    untagged,
+                }
+            }
+            err @ (InferError::InvalidSchema(_)
+            | InferError::SampleRejected { .. }
+            | InferError::InvalidJson(_)
+            | InferError::DuplicateDefinition(_)
+            | InferError::UnknownNumType(_)) => err,
+        }
+    }
+}
+
+impl fmt::Display for InferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InferError::TypeHintMismatch {
+                path,
+                type_,
+                inferred,
+            } => {
+                write!(
+                    f,
+                    "type hint at {:?} is {}, but examples there were inferred as {}",
+                    to_json_pointer(path),
+                    type_name(type_),
+                    inferred
+                )
+            }
+            InferError::EnumHintMismatch { path, inferred } => write!(
+                f,
+                "enum hint at {:?} is for examples inferred as {}, but only strings and numbers can be an enum",
+                to_json_pointer(path),
+                inferred
+            ),
+            InferError::DiscriminatorHintWithoutTag { path } => write!(
+                f,
+                "discriminator hint at {:?} doesn't name a tag; point it to the tag property instead, such as {:?}",
+                to_json_pointer(path),
+                to_json_pointer(&[path.as_slice(), &["type".to_owned()]].concat())
+            ),
+            InferError::DiscriminatorTagMissing {
+                path,
+                discriminator,
+                untagged,
+            } => write!(
+                f,
+                "discriminator hint at {:?} needs every object at {:?} to have a string {:?} property, but {} {} didn't",
+                to_json_pointer(&[path.as_slice(), std::slice::from_ref(discriminator)].concat()),
+                to_json_pointer(path),
+                discriminator,
+                untagged,
+                if *untagged == 1 { "object" } else { "objects" }
+            ),
+            InferError::InvalidSchema(err) => {
+                write!(f, "inferred an invalid schema, which is a bug: {}", err)
+            }
+            InferError::SampleRejected {
+                index,
+                instance_path,
+                schema_path,
+            } => write!(
+                f,
+                "inferred schema rejects example {} at {:?}, from {:?}",
+                index,
+                to_json_pointer(instance_path),
+                to_json_pointer(schema_path)
+            ),
+            InferError::InvalidJson(message) => write!(f, "invalid JSON input: {}", message),
+            InferError::DuplicateDefinition(name) => write!(
+                f,
+                "can't name the root schema {:?}, as there's already a definition by that name",
+                name
+            ),
+            InferError::UnknownNumType(name) => write!(
+                f,
+                "unknown number type {:?}, expected one of int8, uint8, int16, uint16, int32, uint32, float32, or float64",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InferError {}
+
+/// Formats a path, in the same form as the paths given to
+/// [`Hints`][`crate::Hints`], as a JSON Pointer. The reverse of
+/// [`parse_json_pointer`][`crate::parse_json_pointer`].
+///
+/// ```
+/// use jtd_infer::to_json_pointer;
+///
+/// assert_eq!("", to_json_pointer(&[]));
+/// assert_eq!("/a~1b/~0/-", to_json_pointer(&["a/b".into(), "~".into(), "-".into()]));
+/// ```
+pub fn to_json_pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// The path that a JSON Pointer points to, or `None` if it isn't a valid JSON
+/// Pointer. The reverse of [`to_json_pointer`].
+pub(crate) fn from_json_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(vec![]);
+    }
+
+    pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(|segment| {
+            // Any tilde has to be part of an escape.
+            let unescaped = segment.replace("~1", "/").replace("~0", "~");
+            let escapes = segment.matches("~0").count() + segment.matches("~1").count();
+            (segment.matches('~').count() == escapes).then_some(unescaped)
+        })
+        .collect()
+}
+
+/// The name of a JSON Typedef type, as it appears in a schema.
+pub(crate) fn type_name(type_: &Type) -> &'static str {
+    match type_ {
+        Type::Boolean => "boolean",
+        Type::Float32 => "float32",
+        Type::Float64 => "float64",
+        Type::Int8 => "int8",
+        Type::Uint8 => "uint8",
+        Type::Int16 => "int16",
+        Type::Uint16 => "uint16",
+        Type::Int32 => "int32",
+        Type::Uint32 => "uint32",
+        Type::String => "string",
+        Type::Timestamp => "timestamp",
+    }
+}
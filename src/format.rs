@@ -0,0 +1,459 @@
+use crate::hints::{BooleanStrings, DateDetection};
+use chrono::{DateTime, NaiveDate};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Recognizes strings of a particular format, such as dates or email
+/// addresses.
+///
+/// A position whose strings a detector recognizes is inferred as
+/// `{ "type": "string" }`, with the format the detector returns as `format` in
+/// its `metadata`, or under the detector's
+/// [`metadata_key`][`Self::metadata_key`] if it has another. If more than one
+/// detector with the same key recognizes the strings, the first one wins. See
+/// [`Hints`][`crate::Hints`] for the order detectors are consulted in.
+///
+/// Detectors are given the strings seen at a position all at once, when the
+/// schema is made, and so they can decide from the strings as a whole: say, by
+/// allowing a few strings that don't fit. Past
+/// [`Hints::with_format_sample_size`][`crate::Hints::with_format_sample_size`]
+/// strings, they're given a sample. For instance, here's a detector for strings
+/// of capital letters:
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::{Hints, Inferrer, StringFormatDetector};
+///
+/// struct Uppercase;
+///
+/// impl StringFormatDetector for Uppercase {
+///     fn detect(&self, samples: &[&str]) -> Option<String> {
+///         let uppercase = |s: &&str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_uppercase());
+///
+///         if !samples.is_empty() && samples.iter().all(uppercase) {
+///             Some("uppercase".to_owned())
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let hints = Hints::default().with_format_detector(&Uppercase);
+/// let inferrer = Inferrer::new(hints).infer(json!("ABC")).infer(json!("XYZ"));
+///
+/// assert_eq!(
+///     json!({ "type": "string", "metadata": { "format": "uppercase" } }),
+///     inferrer.into_json().unwrap(),
+/// )
+/// ```
+pub trait StringFormatDetector {
+    /// The name of the format that `samples` are of, as it should appear in
+    /// the schema's `metadata`, or `None` if they aren't of this format.
+    fn detect(&self, samples: &[&str]) -> Option<String>;
+
+    /// The key in the schema's `metadata` that the format is recorded under.
+    fn metadata_key(&self) -> &str {
+        "format"
+    }
+}
+
+/// The format detectors that some hints turn on, in order of precedence.
+///
+/// Timestamps aren't among them. JSON Typedef has a type of its own for them,
+/// and so they're inferred as `{ "type": "timestamp" }`, rather than as a
+/// string with a format in its `metadata`. See [`is_timestamp`].
+pub(crate) struct FormatRegistry<'a> {
+    detectors: Vec<&'a dyn StringFormatDetector>,
+}
+
+impl<'a> FormatRegistry<'a> {
+    pub(crate) fn new(detectors: Vec<&'a dyn StringFormatDetector>) -> Self {
+        Self { detectors }
+    }
+
+    /// Whether no detectors are turned on, and so there's no need to keep any
+    /// strings for them.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.detectors.is_empty()
+    }
+
+    /// The formats recorded in the `metadata` of a string whose examples
+    /// include `samples`. Each key is given the format of the first detector
+    /// with that key that recognized the samples.
+    pub(crate) fn detect(&self, samples: &[&str]) -> BTreeMap<String, Value> {
+        let mut metadata = BTreeMap::new();
+        for detector in &self.detectors {
+            if let Some(format) = detector.detect(samples) {
+                metadata
+                    .entry(detector.metadata_key().to_owned())
+                    .or_insert(Value::String(format));
+            }
+        }
+
+        metadata
+    }
+}
+
+/// `format`, if there are samples and every one satisfies `is_format`.
+fn every(samples: &[&str], format: &str, is_format: impl Fn(&str) -> bool) -> Option<String> {
+    if !samples.is_empty() && samples.iter().all(|s| is_format(s)) {
+        Some(format.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Whether `s` is a timestamp, as RFC 3339 describes them, which is what JSON
+/// Typedef accepts as a `timestamp`.
+///
+/// Unlike other formats, timestamps change the type of the schema, and a
+/// schema of type `timestamp` rejects any string that isn't one. So this is
+/// checked for every string as it's seen, rather than from a sample.
+pub(crate) fn is_timestamp(s: &str) -> bool {
+    DateTime::parse_from_rfc3339(s).is_ok()
+}
+
+/// Detects date-only strings, of the form `YYYY-MM-DD`.
+///
+/// The shape is checked exactly, so that things like `20200101` or `2020-1-1`
+/// are never taken to be dates.
+impl StringFormatDetector for DateDetection {
+    fn detect(&self, samples: &[&str]) -> Option<String> {
+        every(samples, "date", |s| self.is_date(s))
+    }
+}
+
+impl DateDetection {
+    fn is_date(&self, s: &str) -> bool {
+        let shape_matches = s.len() == 10
+            && s.bytes().enumerate().all(|(i, b)| match i {
+                4 | 7 => b == b'-',
+                _ => b.is_ascii_digit(),
+            });
+
+        match self {
+            DateDetection::Off => false,
+            DateDetection::Lenient => shape_matches,
+            DateDetection::Strict => {
+                shape_matches && NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+            }
+        }
+    }
+}
+
+/// Detects the strings that stand in for booleans.
+impl StringFormatDetector for BooleanStrings {
+    fn detect(&self, samples: &[&str]) -> Option<String> {
+        every(samples, "boolean", |s| self.matches(s))
+    }
+}
+
+/// Detects strings that look like email addresses.
+///
+/// This is a pragmatic check rather than the full grammar of RFC 5322: there
+/// must be exactly one `@`, with something before it, and a domain after it
+/// made of at least two non-empty, dot-separated labels. Whitespace isn't
+/// allowed anywhere.
+pub(crate) struct EmailAddresses;
+
+impl StringFormatDetector for EmailAddresses {
+    fn detect(&self, samples: &[&str]) -> Option<String> {
+        every(samples, "email", is_email)
+    }
+}
+
+fn is_email(s: &str) -> bool {
+    let (local, domain) = match s.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty())
+        && !s.chars().any(char::is_whitespace)
+}
+
+/// The key in `metadata` that content encodings are recorded under, as in JSON
+/// Schema.
+const CONTENT_ENCODING: &str = "contentEncoding";
+
+/// `format`, if at least one of `samples`, and at least `min_ratio` of them,
+/// satisfy `is_format`.
+fn enough(
+    samples: &[&str],
+    format: &str,
+    min_ratio: f64,
+    is_format: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let matches = samples.iter().filter(|s| is_format(s)).count();
+
+    if matches > 0 && matches as f64 >= min_ratio * samples.len() as f64 {
+        Some(format.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Detects strings of hex digits, as `base16`.
+///
+/// Short strings of hex digits are common in ordinary data, such as `"cafe"` or
+/// `"2024"`, and so a string must be at least `min_length` long, and have both
+/// digits and letters. The letters must be all lowercase or all uppercase.
+/// At least `min_ratio` of the strings must be encoded this way.
+#[derive(Clone, Copy)]
+pub(crate) struct Base16 {
+    pub(crate) min_length: usize,
+    pub(crate) min_ratio: f64,
+}
+
+impl StringFormatDetector for Base16 {
+    fn detect(&self, samples: &[&str]) -> Option<String> {
+        enough(samples, "base16", self.min_ratio, |s| self.is_encoded(s))
+    }
+
+    fn metadata_key(&self) -> &str {
+        CONTENT_ENCODING
+    }
+}
+
+impl Base16 {
+    fn is_encoded(&self, s: &str) -> bool {
+        let bytes = s.as_bytes();
+        let has = |f: fn(&u8) -> bool| bytes.iter().any(f);
+
+        s.len() >= self.min_length
+            && s.len() % 2 == 0
+            && bytes.iter().all(u8::is_ascii_hexdigit)
+            && has(u8::is_ascii_digit)
+            && has(u8::is_ascii_alphabetic)
+            && !(has(u8::is_ascii_lowercase) && has(u8::is_ascii_uppercase))
+    }
+}
+
+/// Detects base64-encoded strings, as `base64`.
+///
+/// A string must be made of the standard alphabet, with `+` and `/`, or of the
+/// URL-safe one, with `-` and `_`, but not both. It must be padded with `=` to
+/// a multiple of four characters, with the padding's bits left as zero, so
+/// that it decodes cleanly.
+///
+/// Words and identifiers are often valid base64 too, and so a string must also
+/// be at least `min_length` long, and have both uppercase and lowercase
+/// letters. Encoded binary data almost always does, while lowercase words and
+/// hex digits don't. At least `min_ratio` of the strings must be encoded this
+/// way.
+#[derive(Clone, Copy)]
+pub(crate) struct Base64 {
+    pub(crate) min_length: usize,
+    pub(crate) min_ratio: f64,
+}
+
+impl StringFormatDetector for Base64 {
+    fn detect(&self, samples: &[&str]) -> Option<String> {
+        enough(samples, "base64", self.min_ratio, |s| self.is_encoded(s))
+    }
+
+    fn metadata_key(&self) -> &str {
+        CONTENT_ENCODING
+    }
+}
+
+impl Base64 {
+    fn is_encoded(&self, s: &str) -> bool {
+        let data = s.trim_end_matches('=');
+        let padding = s.len() - data.len();
+        let bytes = data.as_bytes();
+        let has = |f: fn(&u8) -> bool| bytes.iter().any(f);
+
+        let standard = has(|&b| b == b'+' || b == b'/');
+        let url_safe = has(|&b| b == b'-' || b == b'_');
+        let in_alphabet = bytes
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b"+/-_".contains(&b));
+
+        // The sextet before the padding only carries some bits of the data.
+        // The rest must be zero.
+        let unused_bits = match padding {
+            0 => 0,
+            1 => 2,
+            2 => 4,
+            _ => return false,
+        };
+
+        let last = bytes.last().map(|&b| match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' | b'-' => 62,
+            _ => 63,
+        });
+
+        s.len() >= self.min_length
+            && s.len() % 4 == 0
+            && in_alphabet
+            && !(standard && url_safe)
+            && last.is_some_and(|last| last & ((1 << unused_bits) - 1) == 0)
+            && has(u8::is_ascii_uppercase)
+            && has(u8::is_ascii_lowercase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        for date_detection in [DateDetection::Lenient, DateDetection::Strict] {
+            assert!(date_detection.is_date("2020-02-29"));
+            assert!(!date_detection.is_date("2020-02-29T00:00:00Z"));
+            assert!(!date_detection.is_date("20200229"));
+            assert!(!date_detection.is_date("2020-2-29"));
+        }
+
+        assert!(DateDetection::Lenient.is_date("2021-02-29"));
+        assert!(!DateDetection::Strict.is_date("2021-02-29"));
+        assert!(!DateDetection::Off.is_date("2020-02-29"));
+    }
+
+    #[test]
+    fn boolean_strings() {
+        let boolean_strings = BooleanStrings::default();
+        assert!(boolean_strings.matches("yes"));
+        assert!(!boolean_strings.matches("Yes"));
+        assert!(boolean_strings.with_ignore_case(true).matches("Yes"));
+    }
+
+    #[test]
+    fn email_addresses() {
+        for email in ["alice@example.com", "bob.smith+x@mail.example.org", "c@d.e"] {
+            assert!(is_email(email), "{:?}", email);
+        }
+
+        // Strings that merely contain an @ aren't email addresses.
+        for not_email in [
+            "@example.com",
+            "alice@",
+            "alice@localhost",
+            "alice@example.",
+            "alice@.com",
+            "alice@example..com",
+            "alice@bob@example.com",
+            "alice @example.com",
+            "meet @ example.com",
+        ] {
+            assert!(!is_email(not_email), "{:?}", not_email);
+        }
+    }
+
+    #[test]
+    fn base16() {
+        let base16 = Base16 {
+            min_length: 16,
+            min_ratio: 1.0,
+        };
+        assert!(base16.is_encoded("00deadbeef112233"));
+        assert!(base16.is_encoded("00DEADBEEF112233"));
+
+        for not_base16 in [
+            "deadbeef",
+            "00deadbeef11223",
+            "0123456789012345",
+            "deadbeefcafebabe",
+            "00DEADbeef112233",
+            "00deadbeef11223g",
+        ] {
+            assert!(!base16.is_encoded(not_base16), "{:?}", not_base16);
+        }
+    }
+
+    #[test]
+    fn base64() {
+        let base64 = Base64 {
+            min_length: 16,
+            min_ratio: 1.0,
+        };
+        for encoded in [
+            "SGVsbG8sIHdvcmxkIQ==",
+            "aGVsbG8gd29ybGQ/Pz8/",
+            "aGVsbG8gd29ybGQ_Pz8_",
+            "AAECAwQFBgcICQoLDA0ODw==",
+        ] {
+            assert!(base64.is_encoded(encoded), "{:?}", encoded);
+        }
+
+        for not_base64 in [
+            "SGVsbG8=",
+            "helloworldfoobar",
+            "SGVsbG8sIHdvcmxkIQ",
+            "SGVsbG8sIHdvcmxkIR==",
+            "SGVsbG8sIHdvcmxkIQ===",
+            "aGVsbG8gd29ybGQ/Pz8_",
+            "SGVsbG8s IHdvcmxkIQ=",
+        ] {
+            assert!(!base64.is_encoded(not_base64), "{:?}", not_base64);
+        }
+    }
+
+    #[test]
+    fn timestamps() {
+        assert!(is_timestamp("2020-01-01T00:00:00Z"));
+        assert!(is_timestamp("2020-01-01T00:00:00.5+01:00"));
+        assert!(!is_timestamp("2020-01-01"));
+        assert!(!is_timestamp("yesterday"));
+    }
+
+    #[test]
+    fn registry() {
+        let date_detection = DateDetection::Lenient;
+        let registry = FormatRegistry::new(vec![&date_detection, &EmailAddresses]);
+
+        assert_eq!(
+            vec![("format".to_owned(), Value::String("date".to_owned()))]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+            registry.detect(&["2020-01-01", "2020-12-31"])
+        );
+
+        // Every sample must fit, and with no samples, nothing does.
+        assert!(registry
+            .detect(&["2020-01-01", "2020-01-01T00:00:00Z"])
+            .is_empty());
+        assert!(registry.detect(&[]).is_empty());
+
+        assert!(!registry.is_empty());
+        assert!(FormatRegistry::new(vec![]).is_empty());
+    }
+
+    #[test]
+    fn validity_ratio() {
+        // Nine of these ten strings are hex digits.
+        let mut samples = vec!["00deadbeef112233"; 9];
+        samples.push("not hex at all!!");
+
+        for (min_ratio, detected) in [(1.0, false), (0.95, false), (0.9, true), (0.5, true)] {
+            let base16 = Base16 {
+                min_length: 16,
+                min_ratio,
+            };
+
+            assert_eq!(
+                detected,
+                base16.detect(&samples).is_some(),
+                "{:?}",
+                min_ratio
+            );
+        }
+
+        let base64 = Base64 {
+            min_length: 16,
+            min_ratio: 0.0,
+        };
+
+        // There must always be some encoded strings.
+        assert_eq!(None, base64.detect(&["not base64 at all"]));
+        assert_eq!(None, base64.detect(&[]));
+    }
+}
@@ -1,4 +1,8 @@
+use crate::format::{Base16, Base64, EmailAddresses, FormatRegistry, StringFormatDetector};
 use crate::inferred_number::NumType;
+use jtd::Type;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Hints for [`Inferrer`][`crate::Inferrer`].
 ///
@@ -13,6 +17,142 @@ use crate::inferred_number::NumType;
 /// ignored, the inferrer will infer the narrowest numerical type possible for
 /// input data, preferring unsigned integers over signed integers.
 ///
+/// To use a different number type for only some parts of the input, see
+/// [`Hints::with_number_type_hints`].
+///
+/// The default number type only applies to positions where numbers were seen.
+/// A position that only ever saw nulls, or never saw any data at all (such as
+/// the elements of arrays that were always empty), has nothing to infer a type
+/// from, and so by default it's inferred as the empty form, `{}`. What these
+/// positions become instead is set with [`Hints::with_empty_fallback`]; see
+/// [`EmptyFallback`]. A position that saw numbers alongside other kinds of
+/// data is a different matter; see `mixed_scalars` below.
+///
+/// Integers too large for 64 bits, like `123456789012345678901234567890`, are
+/// inferred as `float64` by default, which can only approximate them. See
+/// [`BigIntegers`] for the alternative, set with [`Hints::with_big_integers`].
+///
+/// `open_objects` tells [`Inferrer`][`crate::Inferrer`] to infer objects that
+/// allow properties beyond the ones seen in the examples, by setting
+/// `additionalProperties` to `true`. By default, inferred objects are closed.
+/// See [`Hints::with_open_objects`]. To only open up some objects, use
+/// [`Hints::with_open_object_paths`] instead.
+///
+/// `any_paths` point to parts of the input that are free-form, and should be
+/// inferred as the empty form, `{}`, no matter what data is seen there. See
+/// [`Hints::with_any_paths`].
+///
+/// `histograms` tells [`Inferrer`][`crate::Inferrer`] to count the numbers it
+/// sees at each position, bucketed by order of magnitude, and to emit those
+/// counts as `histogram` in the `metadata` of the position's schema. See
+/// [`Hints::with_histograms`].
+///
+/// `tuples` tells [`Inferrer`][`crate::Inferrer`] to also infer arrays index
+/// by index, for as long as they're all the same length, and to emit the
+/// schema of each index as `tuple` in the `metadata` of the array's schema.
+/// See [`Hints::with_tuples`].
+///
+/// `key_descriptions` tells [`Inferrer`][`crate::Inferrer`] to make up a
+/// `description` in the `metadata` of each property from its key. See
+/// [`Hints::with_key_descriptions`].
+///
+/// JSON Typedef enums can only contain strings. If an enum hint points to
+/// integers, the inferrer emits an enum of those integers written out as
+/// strings, with `"numericEnum": true` in the schema's `metadata`, so that code
+/// generators can turn it back into an integer enum. Note that such a schema
+/// does not itself accept the original, numeric, data. If a number that isn't
+/// an integer shows up, the position is inferred as an ordinary number instead.
+///
+/// `date_detection` tells [`Inferrer`][`crate::Inferrer`] to look for strings
+/// that are calendar dates, written as `YYYY-MM-DD`. JSON Typedef has no date
+/// type, and so a position where every string is a date is inferred as
+/// `{ "type": "string" }` with `"format": "date"` in its `metadata`. By
+/// default, dates are not detected. See [`DateDetection`].
+///
+/// `email_detection` tells [`Inferrer`][`crate::Inferrer`] to look for
+/// strings that look like email addresses. A position where every string does
+/// is inferred as `{ "type": "string" }` with `"format": "email"` in its
+/// `metadata`. The check is deliberately simple, so that it's predictable: see
+/// [`Hints::with_email_detection`]. By default, email addresses are not
+/// detected.
+///
+/// `boolean_strings` tells [`Inferrer`][`crate::Inferrer`] to look for
+/// strings that stand in for booleans, such as `"yes"` and `"no"`. A position
+/// where every string is one of those is inferred as `{ "type": "string" }`
+/// with `"format": "boolean"` in its `metadata`. By default, such strings are
+/// not detected. See [`BooleanStrings`].
+///
+/// `content_encodings` tells [`Inferrer`][`crate::Inferrer`] to look for
+/// strings of binary data, encoded as hex digits or as base64. A position where
+/// every string is encoded one way is inferred as `{ "type": "string" }` with
+/// `"contentEncoding": "base16"` or `"contentEncoding": "base64"` in its
+/// `metadata`, as in JSON Schema. Short strings are never taken to be encoded,
+/// since ordinary words are often valid base64: see
+/// [`Hints::with_content_encodings`]. By default, encodings are not detected.
+///
+/// Other formats can be detected by adding a [`StringFormatDetector`] with
+/// [`Hints::with_format_detector`]. When more than one format fits all of the
+/// strings at a position, the detectors added this way come first, in the
+/// order they were added, followed by dates, email addresses, boolean-like
+/// strings, and content encodings. Content encodings are recorded under their
+/// own key, and so they can show up alongside another format. Timestamps come
+/// before all of them: strings that are all RFC 3339 timestamps are inferred as
+/// `{ "type": "timestamp" }`, with no format.
+///
+/// `mixed_scalars` decides what a position becomes when it's seen scalars of
+/// more than one kind, such as both numbers and strings. JSON Typedef has no
+/// type that accepts such a mix, and so by default the position is inferred as
+/// the empty form, `{}`, which accepts anything. See [`MixedScalars`].
+///
+/// `case_insensitive_keys` tells [`Inferrer`][`crate::Inferrer`] to treat
+/// object keys that differ only in case, such as `userId` and `UserID`, as the
+/// same property. The first spelling seen is the one that appears in the
+/// schema. See [`Hints::with_case_insensitive_keys`].
+///
+/// `nested_values` makes values hints recurse. When it's set, objects among
+/// the values of a map inferred under a values hint are themselves inferred as
+/// maps, and so on down, as if each level had its own values hint. The values
+/// at each level are merged across all of the keys of that level. See
+/// [`Hints::with_nested_values`].
+///
+/// `emit_nullable` decides whether the inferrer ever emits `"nullable": true`.
+/// It does by default. For consumers that don't support `nullable`, it can be
+/// turned off, in which case a position that saw nulls is inferred the way
+/// [`NullableFallback`] says instead. See [`Hints::with_emit_nullable`].
+///
+/// `max_enum_size` caps how many distinct values an enum hint will collect.
+/// Once a position sees more distinct strings than that, the inferrer stops
+/// collecting them and infers `{ "type": "string" }` instead. By default, there
+/// is no cap. See [`Hints::with_max_enum_size`].
+///
+/// `integer_enums` lets a number position be inferred as an enum without an
+/// enum hint. A position where every number was an integer, there were no more
+/// than a given number of distinct values, and at least one of them was seen
+/// more than once, is inferred as an enum of the integers written as strings,
+/// with `numericEnum` in its `metadata`. It's off by default. See
+/// [`Hints::with_integer_enums`].
+///
+/// `min_required_samples` guards against over-constraining objects from a
+/// handful of examples. A property that was in every object is only inferred
+/// as required once at least this many objects have been seen where it could
+/// be; otherwise, it's optional. By default, there is no minimum. See
+/// [`Hints::with_min_required_samples`].
+///
+/// `absent_as_nullable` is for consumers that can't handle
+/// `optionalProperties`. A property that was sometimes absent is inferred as a
+/// nullable required property, rather than an optional one. It's off by
+/// default. See [`Hints::with_absent_as_nullable`].
+///
+/// `values_collapse` turns objects whose keys look like data into maps. An
+/// object with at least a given number of optional properties, whose
+/// properties all have the same schema, is inferred as a `values` schema
+/// instead. It's off by default. See [`Hints::with_values_collapse`].
+///
+/// `element_discriminators` lets the objects among the elements of an array be
+/// inferred as a discriminator form without a discriminator hint, if they all
+/// have a string property that takes only a few distinct values. See
+/// [`Hints::with_element_discriminators`].
+///
 /// To adapt the example used at [the crate-level docs][`crate`], here's how you
 /// could change [`Inferrer`][`crate::Inferrer`] behavior using hints:
 ///
@@ -31,8 +171,6 @@ use crate::inferred_number::NumType;
 /// inferrer = inferrer.infer(json!({ "foo": true, "bar": "xxx" }));
 /// inferrer = inferrer.infer(json!({ "foo": false, "bar": null, "baz": 5 }));
 ///
-/// let inference = inferrer.into_schema();
-///
 /// assert_eq!(
 ///     json!({
 ///         "properties": {
@@ -43,7 +181,7 @@ use crate::inferred_number::NumType;
 ///             "baz": { "type": "float32" }, // instead of uint8
 ///         },
 ///     }),
-///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+///     inferrer.into_json().unwrap(),
 /// )
 /// ```
 pub struct Hints<'a> {
@@ -51,6 +189,35 @@ pub struct Hints<'a> {
     enums: HintSet<'a>,
     values: HintSet<'a>,
     discriminator: HintSet<'a>,
+    max_enum_size: Option<usize>,
+    integer_enums: Option<usize>,
+    element_discriminators: Option<usize>,
+    values_collapse: Option<usize>,
+    min_required_samples: usize,
+    absent_as_nullable: bool,
+    open_objects: bool,
+    open_object_paths: HintSet<'a>,
+    any_paths: HintSet<'a>,
+    histograms: bool,
+    tuples: bool,
+    key_descriptions: bool,
+    date_detection: DateDetection,
+    email_detection: bool,
+    content_encodings: Option<(Base16, Base64)>,
+    content_encoding_ratio: f64,
+    boolean_strings: Option<&'a BooleanStrings>,
+    format_detectors: Cow<'a, [&'a dyn StringFormatDetector]>,
+    format_sample_size: usize,
+    mixed_scalars: MixedScalars,
+    case_insensitive_keys: bool,
+    empty_fallback: EmptyFallback,
+    big_integers: BigIntegers,
+    nested_values: bool,
+    emit_nullable: bool,
+    nullable_fallback: NullableFallback,
+    types: HintMap<'a, Type>,
+    num_types: HintMap<'a, NumType>,
+    subtree_num_types: HintMap<'a, NumType>,
 }
 
 impl<'a> Hints<'a> {
@@ -66,22 +233,645 @@ impl<'a> Hints<'a> {
             enums,
             values,
             discriminator,
+            max_enum_size: None,
+            integer_enums: None,
+            element_discriminators: None,
+            values_collapse: None,
+            min_required_samples: 0,
+            absent_as_nullable: false,
+            open_objects: false,
+            open_object_paths: HintSet::new(vec![]),
+            any_paths: HintSet::new(vec![]),
+            histograms: false,
+            tuples: false,
+            key_descriptions: false,
+            date_detection: DateDetection::Off,
+            email_detection: false,
+            content_encodings: None,
+            content_encoding_ratio: 1.0,
+            boolean_strings: None,
+            format_detectors: Cow::Borrowed(&[]),
+            format_sample_size: 256,
+            mixed_scalars: MixedScalars::Empty,
+            case_insensitive_keys: false,
+            empty_fallback: EmptyFallback::Empty,
+            big_integers: BigIntegers::Float,
+            nested_values: false,
+            emit_nullable: true,
+            nullable_fallback: NullableFallback::Empty,
+            types: HintMap::new(vec![]),
+            num_types: HintMap::new(vec![]),
+            subtree_num_types: HintMap::new(vec![]),
         }
     }
 
-    pub(crate) fn default_num_type(&self) -> &NumType {
-        &self.default_num_type
-    }
-
-    pub(crate) fn sub_hints(&self, key: &str) -> Self {
+    /// Constructs a set of [`Hints`] with the given default number type, and
+    /// no other hints.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, NumType};
+    ///
+    /// let inferrer = Inferrer::new(Hints::empty(NumType::Float64)).infer(json!(5));
+    ///
+    /// assert_eq!(
+    ///     json!({ "type": "float64" }),
+    ///     inferrer.into_json().unwrap(),
+    /// )
+    /// ```
+    pub fn empty(default_num_type: NumType) -> Self {
         Self::new(
-            self.default_num_type.clone(),
-            self.enums.sub_hints(key),
-            self.values.sub_hints(key),
-            self.discriminator.sub_hints(key),
+            default_num_type,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
         )
     }
 
+    /// Caps the number of distinct values an enum hint will collect.
+    ///
+    /// Inference never holds on to more than `max_enum_size + 1` distinct
+    /// strings for a position. As soon as that many are seen, the collected
+    /// values are discarded and the position is inferred as a string.
+    pub fn with_max_enum_size(mut self, max_enum_size: usize) -> Self {
+        self.max_enum_size = Some(max_enum_size);
+        self
+    }
+
+    /// Infers numbers that look like a small set of codes as enums, even
+    /// without an enum hint.
+    ///
+    /// A number position becomes an enum of its integers, as strings, if it
+    /// saw no more than `max_size` distinct integers, nothing but integers, and
+    /// at least one of them more than once. Inference never holds on to more
+    /// than `max_size + 1` distinct integers for a position. Positions under an
+    /// enum hint keep following [`Hints::with_max_enum_size`] instead.
+    pub fn with_integer_enums(mut self, max_size: usize) -> Self {
+        self.integer_enums = Some(max_size);
+        self
+    }
+
+    /// Infers the objects in an array as a discriminator form when they all
+    /// share a tag, even without a discriminator hint.
+    ///
+    /// A tag is a property that every object has, always with a string value,
+    /// and with between two and `max_tags` distinct values. The objects are
+    /// then inferred as if there were a discriminator hint for it. If more than
+    /// one property could be the tag, the one with the fewest distinct values
+    /// is used, and among those, the first in key order. If none could be, the
+    /// objects are merged as usual.
+    ///
+    /// Until the examples run out, every string property of the first object
+    /// remains a candidate, unless it's ruled out. Each candidate costs about as
+    /// much memory as inferring the objects once more.
+    pub fn with_element_discriminators(mut self, max_tags: usize) -> Self {
+        self.element_discriminators = Some(max_tags);
+        self
+    }
+
+    /// Infers an object as a `values` schema, rather than a `properties` one,
+    /// when it has at least `min_optional` optional properties and all of its
+    /// properties have the same schema.
+    ///
+    /// This is for objects keyed by IDs, dates, and the like, which without a
+    /// values hint become a long list of optional properties. The `values`
+    /// schema accepts every example the `properties` one would have, but also
+    /// any other key. The variants of a discriminator are never collapsed,
+    /// since JSON Typedef requires them to be `properties` schemas.
+    pub fn with_values_collapse(mut self, min_optional: usize) -> Self {
+        self.values_collapse = Some(min_optional);
+        self
+    }
+
+    /// Only infers properties as required once at least `min_samples` objects
+    /// have been seen at the same position.
+    ///
+    /// The objects counted are the ones at that position, not every example
+    /// given to the inferrer. So an object that was only in some of the
+    /// examples needs `min_samples` of its own before its properties are
+    /// required, however many examples there were in all. With fewer objects
+    /// than that, every property is optional, even if it was in every object.
+    pub fn with_min_required_samples(mut self, min_samples: usize) -> Self {
+        self.min_required_samples = min_samples;
+        self
+    }
+
+    /// Sets whether properties that were sometimes absent are inferred as
+    /// required, but nullable, instead of as optional.
+    ///
+    /// This changes what the schema accepts: an absent property isn't the same
+    /// as a null one, and so the schema rejects the examples that left the
+    /// property out. Data must then always include the property, with a null
+    /// in place of a missing value.
+    ///
+    /// This has no effect when [`Hints::with_emit_nullable`] is `false`.
+    pub fn with_absent_as_nullable(mut self, absent_as_nullable: bool) -> Self {
+        self.absent_as_nullable = absent_as_nullable;
+        self
+    }
+
+    /// Sets whether inferred objects accept properties that weren't seen in the
+    /// examples.
+    pub fn with_open_objects(mut self, open_objects: bool) -> Self {
+        self.open_objects = open_objects;
+        self
+    }
+
+    /// Sets the paths to objects that should accept properties that weren't
+    /// seen in the examples.
+    ///
+    /// Unlike [`Hints::with_open_objects`], this only applies to the objects at
+    /// these paths, and not to the objects nested within them.
+    pub fn with_open_object_paths(mut self, open_object_paths: HintSet<'a>) -> Self {
+        self.open_object_paths = open_object_paths;
+        self
+    }
+
+    /// Sets the paths to parts of the input that should accept anything.
+    ///
+    /// These are always inferred as the empty form, `{}`, and the examples
+    /// seen there aren't looked at, so that nothing nested within them is
+    /// inferred either. This takes precedence over every other hint for the
+    /// same part of the input.
+    pub fn with_any_paths(mut self, any_paths: HintSet<'a>) -> Self {
+        self.any_paths = any_paths;
+        self
+    }
+
+    /// Sets whether to emit histograms of numeric positions in the inferred
+    /// schema's metadata.
+    ///
+    /// Histogram buckets are fixed, one per order of magnitude (positive and
+    /// negative) up to `10^15`, so the memory they take up is bounded no matter
+    /// how much data is seen.
+    pub fn with_histograms(mut self, histograms: bool) -> Self {
+        self.histograms = histograms;
+        self
+    }
+
+    /// Sets whether to keep track of arrays index by index, for data where
+    /// arrays are fixed-length tuples, like `[1, "a", true]`.
+    ///
+    /// JSON Typedef has no tuple form, and so the elements of an array are
+    /// still inferred all together, as whatever accepts every one of them. For
+    /// the tuple above, that's the empty form. But if every array at a
+    /// position was the same length, the schema of each index is emitted in
+    /// order as `tuple` in the `metadata` of the array's schema, so that tools
+    /// downstream can recover the structure. If the arrays' lengths varied, or
+    /// they were all empty, there's no `tuple`.
+    pub fn with_tuples(mut self, tuples: bool) -> Self {
+        self.tuples = tuples;
+        self
+    }
+
+    /// Sets whether to give each property a `description` in its `metadata`,
+    /// made from its key, as a starting point for documenting the schema.
+    ///
+    /// The key is split into words at underscores, dashes, and changes of
+    /// case, and written as a sentence, so that `createdAt` and `created_at`
+    /// are both described as "Created at". A run of capitals is kept as an
+    /// acronym, as in "User ID" for `userID`. Keys without any letters or
+    /// digits aren't described.
+    pub fn with_key_descriptions(mut self, key_descriptions: bool) -> Self {
+        self.key_descriptions = key_descriptions;
+        self
+    }
+
+    /// Sets whether, and how strictly, to detect strings that are calendar
+    /// dates.
+    pub fn with_date_detection(mut self, date_detection: DateDetection) -> Self {
+        self.date_detection = date_detection;
+        self
+    }
+
+    /// Sets whether to look for strings that are email addresses.
+    ///
+    /// A string counts as an email address if it has exactly one `@`, with
+    /// something before it and a domain with at least one dot after it, and no
+    /// whitespace. So `alice@example.com` is one, while `alice@localhost` and
+    /// `@alice` are not.
+    pub fn with_email_detection(mut self, email_detection: bool) -> Self {
+        self.email_detection = email_detection;
+        self
+    }
+
+    /// Looks for strings that are hex digits or base64, if they're at least
+    /// `min_length` characters long.
+    ///
+    /// By default, every string at a position must decode cleanly: hex digits
+    /// must come in pairs, and base64 must be padded to a multiple of four
+    /// characters. See [`Hints::with_content_encoding_ratio`] to allow some
+    /// strings that don't. To
+    /// keep words and numbers from being mistaken for encoded data, hex digits
+    /// must include both digits and letters, of a single case, and base64 must
+    /// include both uppercase and lowercase letters. Hex digits take
+    /// precedence, since most of them are also valid base64.
+    ///
+    /// A `min_length` of 16 is enough to rule out most words, while still
+    /// detecting 8-byte values in hex and 12-byte values in base64.
+    pub fn with_content_encodings(mut self, min_length: usize) -> Self {
+        let min_ratio = self.content_encoding_ratio;
+        self.content_encodings = Some((
+            Base16 {
+                min_length,
+                min_ratio,
+            },
+            Base64 {
+                min_length,
+                min_ratio,
+            },
+        ));
+        self
+    }
+
+    /// Sets how many of the strings at a position must be encoded one way for
+    /// [`Hints::with_content_encodings`] to record the encoding, as a ratio of
+    /// all of them. This is 1 by default, so that every string must be.
+    ///
+    /// A lower ratio tolerates data that's mostly encoded, with the odd
+    /// placeholder or malformed value, such as a ratio of 0.9 for a position
+    /// where nine in ten strings are base64. At least one string must be
+    /// encoded either way. The encoding is only recorded in the `metadata`, and
+    /// so the schema still accepts every string.
+    pub fn with_content_encoding_ratio(mut self, min_ratio: f64) -> Self {
+        self.content_encoding_ratio = min_ratio;
+        if let Some((base16, base64)) = &mut self.content_encodings {
+            base16.min_ratio = min_ratio;
+            base64.min_ratio = min_ratio;
+        }
+
+        self
+    }
+
+    /// Sets which strings to treat as standing in for booleans.
+    pub fn with_boolean_strings(mut self, boolean_strings: &'a BooleanStrings) -> Self {
+        self.boolean_strings = Some(boolean_strings);
+        self
+    }
+
+    /// Adds a detector for another format of string.
+    ///
+    /// This can be called more than once to add several detectors. They take
+    /// precedence over the built-in ones, and over each other in the order they
+    /// were added.
+    pub fn with_format_detector(mut self, detector: &'a dyn StringFormatDetector) -> Self {
+        self.format_detectors.to_mut().push(detector);
+        self
+    }
+
+    /// Sets how many of the strings seen at each position are kept for format
+    /// detectors to go over, which is 256 by default.
+    ///
+    /// Up to this many strings, detection takes every string into account.
+    /// Beyond it, the strings kept are a sample, and each string seen is as
+    /// likely as any other to be among them. Formats are only recorded in the
+    /// schema's `metadata`, and so a sample that misses a string that doesn't
+    /// fit never makes the schema reject that string. A size of 0 turns off
+    /// format detection.
+    ///
+    /// Timestamps are detected from every string, however many are kept, and
+    /// strings are only kept at all if some format detector is turned on.
+    pub fn with_format_sample_size(mut self, format_sample_size: usize) -> Self {
+        self.format_sample_size = format_sample_size;
+        self
+    }
+
+    /// Sets what to infer for positions that have seen more than one kind of
+    /// scalar.
+    pub fn with_mixed_scalars(mut self, mixed_scalars: MixedScalars) -> Self {
+        self.mixed_scalars = mixed_scalars;
+        self
+    }
+
+    /// Sets whether object keys that differ only in case are merged into one
+    /// property.
+    ///
+    /// Note that the resulting schema only accepts the spelling of each key
+    /// that was seen first, since JSON Typedef properties are case-sensitive.
+    pub fn with_case_insensitive_keys(mut self, case_insensitive_keys: bool) -> Self {
+        self.case_insensitive_keys = case_insensitive_keys;
+        self
+    }
+
+    /// Sets what to infer for positions that never saw any data besides
+    /// nulls.
+    pub fn with_empty_fallback(mut self, empty_fallback: EmptyFallback) -> Self {
+        self.empty_fallback = empty_fallback;
+        self
+    }
+
+    /// Sets whether positions that never saw any data besides nulls are
+    /// inferred as the default number type, rather than the empty form.
+    ///
+    /// If the position did see nulls, the number type is nullable. This is the
+    /// same as [`Hints::with_empty_fallback`] with [`EmptyFallback::Number`],
+    /// or with [`EmptyFallback::Empty`] if `unknown_as_number` is `false`.
+    pub fn with_unknown_as_number(self, unknown_as_number: bool) -> Self {
+        self.with_empty_fallback(if unknown_as_number {
+            EmptyFallback::Number
+        } else {
+            EmptyFallback::Empty
+        })
+    }
+
+    /// Sets what to infer for positions that saw integers too large for an
+    /// `i64` or a `u64`.
+    pub fn with_big_integers(mut self, big_integers: BigIntegers) -> Self {
+        self.big_integers = big_integers;
+        self
+    }
+
+    /// Sets whether values hints recurse into objects among the values of the
+    /// maps they point to.
+    ///
+    /// For example, with a values hint for the root of the input and this set,
+    /// `{ "a": { "x": 1 }, "b": { "y": 2 } }` is inferred as a map of maps of
+    /// numbers, rather than a map of objects with `x` and `y` properties.
+    pub fn with_nested_values(mut self, nested_values: bool) -> Self {
+        self.nested_values = nested_values;
+        self
+    }
+
+    /// Sets whether schemas may be nullable.
+    ///
+    /// When this is `false`, positions that saw nulls are instead inferred as
+    /// set with [`Hints::with_nullable_fallback`]. Absent properties are still
+    /// inferred as optional properties either way.
+    pub fn with_emit_nullable(mut self, emit_nullable: bool) -> Self {
+        self.emit_nullable = emit_nullable;
+        self
+    }
+
+    /// Sets what to infer for positions that saw nulls, when
+    /// [`Hints::with_emit_nullable`] is `false`.
+    pub fn with_nullable_fallback(mut self, nullable_fallback: NullableFallback) -> Self {
+        self.nullable_fallback = nullable_fallback;
+        self
+    }
+
+    /// Sets the paths to parts of the input that should be given a particular
+    /// type, regardless of what type would be inferred for them.
+    ///
+    /// If the examples at a path can't all be described by the type it's hinted
+    /// to have, then [`Inferrer::into_schema`][`crate::Inferrer::into_schema`]
+    /// returns an error.
+    pub fn with_type_hints(mut self, types: HintMap<'a, Type>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Sets the paths to parts of the input that should use a particular
+    /// number type, in place of the default number type.
+    ///
+    /// These are treated just like the default number type: if the numbers at
+    /// a path don't fit in its hinted type, the narrowest type that does fit
+    /// is inferred instead. To insist on a type, use
+    /// [`Hints::with_type_hints`], which takes precedence over these.
+    pub fn with_number_type_hints(mut self, num_types: HintMap<'a, NumType>) -> Self {
+        self.num_types = num_types;
+        self
+    }
+
+    /// Sets the paths to parts of the input whose numbers, and every number
+    /// within them, should use a particular number type, in place of the
+    /// default number type.
+    ///
+    /// This is the default number type for a whole subtree of the input, and
+    /// is only a preference in the same way. Where more than one hint applies
+    /// to the same numbers, the most specific wins:
+    ///
+    /// 1. A number type hint from [`Hints::with_number_type_hints`] for those
+    ///    numbers' position.
+    /// 2. The subtree hint with the longest path among those that contain the
+    ///    position, the position itself included.
+    /// 3. The default number type.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{HintMap, Hints, Inferrer, NumType};
+    ///
+    /// let payload = vec!["payload".to_string()];
+    /// let counts = vec!["payload".to_string(), "counts".to_string()];
+    /// let hints = Hints::default().with_subtree_number_type_hints(HintMap::new(vec![
+    ///     (&payload[..], NumType::Uint32),
+    ///     (&counts[..], NumType::Uint8),
+    /// ]));
+    ///
+    /// let inferrer = Inferrer::new(hints).infer(json!({
+    ///     "total": 1,
+    ///     "payload": { "id": 1, "counts": { "views": 1 } },
+    /// }));
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "total": { "type": "uint8" },
+    ///             "payload": {
+    ///                 "properties": {
+    ///                     "id": { "type": "uint32" },
+    ///                     "counts": { "properties": { "views": { "type": "uint8" } } },
+    ///                 },
+    ///             },
+    ///         },
+    ///     }),
+    ///     inferrer.into_json().unwrap(),
+    /// );
+    /// ```
+    pub fn with_subtree_number_type_hints(
+        mut self,
+        subtree_num_types: HintMap<'a, NumType>,
+    ) -> Self {
+        self.subtree_num_types = subtree_num_types;
+        self
+    }
+
+    /// The number type to try first for the current position.
+    pub(crate) fn num_type(&self) -> &NumType {
+        self.num_types
+            .active()
+            .or_else(|| self.subtree_num_types.active())
+            .unwrap_or(&self.default_num_type)
+    }
+
+    /// Every format detector that's turned on, in order of precedence.
+    pub(crate) fn format_registry(&self) -> FormatRegistry<'_> {
+        let mut detectors = self.format_detectors.to_vec();
+
+        if self.date_detection != DateDetection::Off {
+            detectors.push(&self.date_detection);
+        }
+
+        if self.email_detection {
+            detectors.push(&EmailAddresses);
+        }
+
+        if let Some(boolean_strings) = self.boolean_strings {
+            detectors.push(boolean_strings);
+        }
+
+        if let Some((base16, base64)) = &self.content_encodings {
+            detectors.push(base16);
+            detectors.push(base64);
+        }
+
+        FormatRegistry::new(detectors)
+    }
+
+    /// How many strings to keep at each position for the format detectors,
+    /// which is none if no detectors are turned on.
+    pub(crate) fn format_sample_size(&self) -> usize {
+        if self.format_registry().is_empty() {
+            0
+        } else {
+            self.format_sample_size
+        }
+    }
+
+    pub(crate) fn mixed_scalars(&self) -> MixedScalars {
+        self.mixed_scalars
+    }
+
+    pub(crate) fn case_insensitive_keys(&self) -> bool {
+        self.case_insensitive_keys
+    }
+
+    pub(crate) fn empty_fallback(&self) -> EmptyFallback {
+        self.empty_fallback
+    }
+
+    pub(crate) fn big_integers(&self) -> BigIntegers {
+        self.big_integers
+    }
+
+    /// What to infer for a position that saw nulls, or `None` if it should
+    /// simply be made nullable.
+    pub(crate) fn nullable_fallback(&self) -> Option<NullableFallback> {
+        if self.emit_nullable {
+            None
+        } else {
+            Some(self.nullable_fallback)
+        }
+    }
+
+    /// Whether properties that were in every one of `objects` objects may be
+    /// inferred as required.
+    pub(crate) fn allows_required(&self, objects: usize) -> bool {
+        objects >= self.min_required_samples
+    }
+
+    /// Whether properties that were sometimes absent should be inferred as
+    /// required and nullable.
+    pub(crate) fn absent_as_nullable(&self) -> bool {
+        self.absent_as_nullable && self.emit_nullable
+    }
+
+    pub(crate) fn max_enum_size(&self) -> Option<usize> {
+        self.max_enum_size
+    }
+
+    /// How many distinct integers a number position may collect as an enum.
+    /// `None` means integers aren't collected at all, and `Some(None)` that
+    /// there's no cap, which only happens under an enum hint.
+    pub(crate) fn numeric_enum_size(&self) -> Option<Option<usize>> {
+        if self.is_enum_active() {
+            Some(self.max_enum_size)
+        } else {
+            self.integer_enums.map(Some)
+        }
+    }
+
+    /// The most distinct values a tag may take, if objects should be checked
+    /// for a tag here. Other hints that decide what an object becomes take
+    /// precedence.
+    pub(crate) fn max_element_tags(&self) -> Option<usize> {
+        if self.is_values_active()
+            || self.peek_active_discriminator().is_some()
+            || self.active_type().is_some()
+        {
+            None
+        } else {
+            self.element_discriminators
+        }
+    }
+
+    pub(crate) fn values_collapse(&self) -> Option<usize> {
+        self.values_collapse
+    }
+
+    pub(crate) fn histograms(&self) -> bool {
+        self.histograms
+    }
+
+    pub(crate) fn tuples(&self) -> bool {
+        self.tuples
+    }
+
+    pub(crate) fn key_descriptions(&self) -> bool {
+        self.key_descriptions
+    }
+
+    pub(crate) fn is_open_object_active(&self) -> bool {
+        self.open_objects || self.open_object_paths.is_active()
+    }
+
+    pub(crate) fn sub_hints(&self, key: &str) -> Hints<'_> {
+        Hints {
+            // A subtree hint here is the default for everything within.
+            default_num_type: *self
+                .subtree_num_types
+                .active()
+                .unwrap_or(&self.default_num_type),
+            enums: self.enums.sub_hints(key),
+            values: self.values.sub_hints(key),
+            discriminator: self.discriminator.sub_hints(key),
+            max_enum_size: self.max_enum_size,
+            integer_enums: self.integer_enums,
+            element_discriminators: self.element_discriminators,
+            values_collapse: self.values_collapse,
+            min_required_samples: self.min_required_samples,
+            absent_as_nullable: self.absent_as_nullable,
+            open_objects: self.open_objects,
+            open_object_paths: self.open_object_paths.sub_hints(key),
+            any_paths: self.any_paths.sub_hints(key),
+            histograms: self.histograms,
+            tuples: self.tuples,
+            key_descriptions: self.key_descriptions,
+            date_detection: self.date_detection,
+            email_detection: self.email_detection,
+            content_encodings: self.content_encodings,
+            content_encoding_ratio: self.content_encoding_ratio,
+            boolean_strings: self.boolean_strings,
+            // Borrowed, rather than copied for every key descended into.
+            format_detectors: Cow::Borrowed(&self.format_detectors),
+            format_sample_size: self.format_sample_size,
+            mixed_scalars: self.mixed_scalars,
+            case_insensitive_keys: self.case_insensitive_keys,
+            empty_fallback: self.empty_fallback,
+            big_integers: self.big_integers,
+            nested_values: self.nested_values,
+            emit_nullable: self.emit_nullable,
+            nullable_fallback: self.nullable_fallback,
+            types: self.types.sub_hints(key),
+            num_types: self.num_types.sub_hints(key),
+            subtree_num_types: self.subtree_num_types.sub_hints(key),
+        }
+    }
+
+    /// Like [`Hints::sub_hints`], but for the values of a map inferred under a
+    /// values hint.
+    pub(crate) fn values_sub_hints(&self, key: &str) -> Hints<'_> {
+        let mut sub_hints = self.sub_hints(key);
+        if self.nested_values {
+            sub_hints.values = sub_hints.values.with_active();
+        }
+
+        sub_hints
+    }
+
+    pub(crate) fn is_any_active(&self) -> bool {
+        self.any_paths.is_active()
+    }
+
     pub(crate) fn is_enum_active(&self) -> bool {
         self.enums.is_active()
     }
@@ -90,12 +880,63 @@ impl<'a> Hints<'a> {
         self.values.is_active()
     }
 
+    /// Whether a discriminator hint points here. That's only right if this is
+    /// the tag, and not the objects that have it.
+    pub(crate) fn is_discriminator_untagged(&self) -> bool {
+        self.discriminator.is_active()
+    }
+
     pub(crate) fn peek_active_discriminator(&self) -> Option<&str> {
         self.discriminator.peek_active()
     }
+
+    pub(crate) fn active_type(&self) -> Option<&Type> {
+        self.types.active()
+    }
+}
+
+pub(crate) const WILDCARD: &str = "-";
+
+/// Whether a segment of a hint's path points to `key`, which is either a
+/// property name or, for array elements and the values of a `values` schema,
+/// [`WILDCARD`].
+fn segment_matches(segment: &str, key: &str) -> bool {
+    segment == WILDCARD || segment == key || (key != WILDCARD && pattern_matches(segment, key))
+}
+
+/// Whether `segment` is a valid pattern in braces, as described at
+/// [`HintSet::new`], that matches all of `key`.
+#[cfg(feature = "regex")]
+fn pattern_matches(segment: &str, key: &str) -> bool {
+    use regex::Regex;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    let pattern = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        Some(pattern) => pattern,
+        None => return false,
+    };
+
+    // Segments are compared against every key seen, and so each pattern is
+    // only compiled once. Invalid patterns are remembered as None.
+    thread_local! {
+        static PATTERNS: RefCell<HashMap<String, Option<Regex>>> = RefCell::new(HashMap::new());
+    }
+
+    PATTERNS.with(|patterns| {
+        patterns
+            .borrow_mut()
+            .entry(pattern.to_owned())
+            .or_insert_with(|| Regex::new(&format!("^(?:{})$", pattern)).ok())
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(key))
+    })
 }
 
-const WILDCARD: &'static str = "-";
+#[cfg(not(feature = "regex"))]
+fn pattern_matches(_segment: &str, _key: &str) -> bool {
+    false
+}
 
 /// A set of paths to parts of the input that are subject to a hint in
 /// [`Hints`].
@@ -118,21 +959,111 @@ impl<'a> HintSet<'a> {
     /// ```
     ///
     /// Creates a set of paths pointing to `/foo/bar` and `/baz` in an input.
+    /// The empty path points to the root of the input.
     ///
     /// The `-` path segment value is special, and acts as a wildcard, matching
     /// any property name. It also matches array elements, unlike ordinary path
-    /// segments.
+    /// segments, and the values of an object inferred as a `values` schema. So
+    /// if `/foo` is a values hint, then `/foo/-` points to the values of that
+    /// map.
+    ///
+    /// With the `regex` feature, a segment wrapped in braces, like
+    /// `{user_\d+}`, is a regular expression, and matches any property name
+    /// that matches it in full. So `/{user_\d+}/score` points to the `score`
+    /// of `user_123` and `user_456` alike. Like an ordinary segment, a pattern
+    /// doesn't match array elements, or the values of a `values` schema. A
+    /// segment in braces that isn't a valid regular expression, or any such
+    /// segment without the feature, only matches a property of the same name,
+    /// braces and all.
     pub fn new(values: Vec<&'a [String]>) -> Self {
         HintSet { values }
     }
 
+    /// Constructs a new [`HintSet`] from paths that are already split into
+    /// segments, as kept in a [`Config`][`crate::Config`]. This is the same as
+    /// [`HintSet::new`], without having to borrow each path as a slice first.
+    ///
+    /// Since each segment is taken as is, there's no escaping to get right, as
+    /// there is for a JSON Pointer: a segment may hold `/` or `~`.
+    ///
+    /// ```
+    /// use jtd_infer::HintSet;
+    ///
+    /// let paths = vec![
+    ///     vec!["foo".to_string(), "bar".to_string()],
+    ///     vec!["a/b".to_string()],
+    /// ];
+    /// let hint_set = HintSet::from_paths(&paths);
+    ///
+    /// assert!(hint_set.matches(&["a/b".to_string()]));
+    /// ```
+    pub fn from_paths(paths: &'a [Vec<String>]) -> Self {
+        Self::new(paths.iter().map(|path| &path[..]).collect())
+    }
+
+    /// Whether any path in the set points to the part of an input at `path`.
+    ///
+    /// `path` is made of the property names that lead there, with `-` for
+    /// array elements and for the values of a `values` schema, in the same
+    /// form as [`CheckedValue::duplicate_keys`][`crate::CheckedValue`]. This
+    /// is how hints are matched up with the input when inferring, wildcards
+    /// and patterns included:
+    ///
+    /// ```
+    /// use jtd_infer::HintSet;
+    ///
+    /// let hint = vec!["users".to_string(), "-".to_string(), "id".to_string()];
+    /// let hint_set = HintSet::new(vec![&hint]);
+    ///
+    /// let path = |segments: &[&str]| -> Vec<String> {
+    ///     segments.iter().map(|s| s.to_string()).collect()
+    /// };
+    /// assert!(hint_set.matches(&path(&["users", "-", "id"])));
+    /// assert!(hint_set.matches(&path(&["users", "alice", "id"])));
+    /// assert!(!hint_set.matches(&path(&["users", "-"])));
+    /// ```
+    pub fn matches(&self, path: &[String]) -> bool {
+        self.values.iter().any(|values| {
+            values.len() == path.len()
+                && values
+                    .iter()
+                    .zip(path)
+                    .all(|(segment, key)| segment_matches(segment, key))
+        })
+    }
+
+    /// The paths in the set, in the order they were given to [`HintSet::new`].
+    ///
+    /// ```
+    /// use jtd_infer::HintSet;
+    ///
+    /// let paths = vec![vec!["a".to_string()], vec![]];
+    /// let hint_set = HintSet::from_paths(&paths);
+    ///
+    /// assert_eq!(vec![&paths[0][..], &paths[1][..]], hint_set.paths().collect::<Vec<_>>());
+    /// assert!(!hint_set.is_empty());
+    /// assert!(HintSet::new(vec![]).is_empty());
+    /// ```
+    pub fn paths(&self) -> impl Iterator<Item = &'a [String]> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// Whether the set has no paths at all, and so its hint applies nowhere.
+    ///
+    /// A set with only the empty path isn't empty: that path points to the
+    /// root of the input.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     pub(crate) fn sub_hints(&self, key: &str) -> Self {
         Self::new(
             self.values
                 .iter()
                 .filter(|values| {
-                    let first = values.first().map(String::as_str);
-                    first == Some(WILDCARD) || first == Some(key)
+                    values
+                        .first()
+                        .is_some_and(|first| segment_matches(first, key))
                 })
                 .map(|values| &values[1..])
                 .collect(),
@@ -143,6 +1074,12 @@ impl<'a> HintSet<'a> {
         self.values.iter().any(|values| values.is_empty())
     }
 
+    /// Adds the empty path to the set, so that it's active.
+    pub(crate) fn with_active(mut self) -> Self {
+        self.values.push(&[]);
+        self
+    }
+
     pub(crate) fn peek_active(&self) -> Option<&str> {
         self.values
             .iter()
@@ -151,6 +1088,246 @@ impl<'a> HintSet<'a> {
     }
 }
 
+/// The same as [`Hints::empty`] with a default number type of
+/// [`NumType::Uint8`].
+impl Default for Hints<'_> {
+    fn default() -> Self {
+        Self::empty(NumType::Uint8)
+    }
+}
+
+/// Whether, and how strictly, to detect date-only strings.
+///
+/// See [`Hints`] for how this enum is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateDetection {
+    /// Don't detect dates. Date-only strings are inferred as plain strings.
+    #[default]
+    Off,
+
+    /// Treat any string of the form `YYYY-MM-DD`, where each letter is an ASCII
+    /// digit, as a date. Strings like `2020-13-45` count as dates.
+    Lenient,
+
+    /// Like [`DateDetection::Lenient`], but the string must also be a valid
+    /// calendar date, such as `2020-02-29` but not `2021-02-29`.
+    Strict,
+}
+
+/// What to infer for a position that has seen more than one kind of scalar:
+/// booleans, numbers, and strings (including timestamps).
+///
+/// See [`Hints`] for how this enum is used. Positions that have seen arrays or
+/// objects alongside anything else are always inferred as the empty form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MixedScalars {
+    /// Infer the empty form, `{}`, which accepts anything.
+    #[default]
+    Empty,
+
+    /// Infer `{ "type": "string" }`. The resulting schema does not itself
+    /// accept the original, non-string, data; this is meant for consumers that
+    /// will coerce every scalar at the position to a string.
+    String,
+}
+
+/// What to infer for a position that has no data to go on: one that only ever
+/// saw nulls, or that never saw any data at all, like the elements of arrays
+/// that were always empty.
+///
+/// See [`Hints`] for how this enum is used. This is unrelated to positions that
+/// saw data of kinds that no one type accepts, such as both numbers and
+/// objects. Those are always inferred as the empty form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyFallback {
+    /// Infer the empty form, `{}`, which accepts anything, null included.
+    #[default]
+    Empty,
+
+    /// Infer the empty form, with `"unknown": true` in its `metadata`.
+    ///
+    /// This accepts the same data as [`EmptyFallback::Empty`], but records
+    /// that nothing is known about the position, as opposed to it having seen
+    /// a mix of data. JSON Typedef's empty form can't be marked `nullable`,
+    /// since it already accepts null, and so this is how a position that was
+    /// only ever null can be told apart.
+    Unknown,
+
+    /// Infer the default number type, which is nullable if the position saw
+    /// nulls. The resulting schema does not accept anything but numbers and
+    /// nulls there.
+    Number,
+}
+
+/// What to infer for a position that saw integers too large for an `i64` or a
+/// `u64`, such as 128-bit IDs.
+///
+/// No JSON Typedef integer type goes beyond 32 bits, and a `float64` only holds
+/// integers up to 2^53 exactly. See [`Hints`] for how this enum is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BigIntegers {
+    /// Infer `float64`, as for any other number too large for the integer
+    /// types. The schema accepts the numbers, but code generated from it may
+    /// round them. This is reported as a
+    /// [`Warning::BigInteger`][`crate::Warning::BigInteger`].
+    #[default]
+    Float,
+
+    /// Infer `{ "type": "string" }`, with `"bigint": true` in its `metadata`.
+    ///
+    /// This is for data whose producer should write these numbers as strings,
+    /// to keep every digit. The schema accepts those strings, but not the
+    /// numbers as they were seen, nor any other number at the same position.
+    String,
+}
+
+/// What to infer for a position that saw nulls, when `nullable` isn't emitted.
+///
+/// See [`Hints`] for how this enum is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NullableFallback {
+    /// Infer the empty form, `{}`, which accepts anything. The resulting schema
+    /// still accepts all of the examples, but is more permissive than a
+    /// nullable one: it no longer checks the non-null data at the position.
+    #[default]
+    Empty,
+
+    /// Infer what the position would have been had its nulls not been there.
+    /// The resulting schema does not itself accept the nulls; this is meant for
+    /// consumers that treat null as equivalent to an absent value.
+    Base,
+}
+
+/// The strings to treat as standing in for booleans.
+///
+/// See [`Hints`] for how this struct is used. By default, the strings are
+/// `"true"`, `"false"`, `"yes"`, and `"no"`, and they must match exactly.
+/// Strings of digits, like `"1"` and `"0"`, are left out by default, since
+/// they're more often numeric codes than booleans.
+///
+/// ```
+/// use jtd_infer::BooleanStrings;
+///
+/// // Accept "Y", "y", "N", and "n".
+/// BooleanStrings::new(vec!["y".to_string(), "n".to_string()]).with_ignore_case(true);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct BooleanStrings {
+    tokens: Vec<String>,
+    ignore_case: bool,
+}
+
+impl BooleanStrings {
+    /// Constructs a new [`BooleanStrings`] from the strings to accept, which
+    /// are matched exactly.
+    pub fn new(tokens: Vec<String>) -> Self {
+        BooleanStrings {
+            tokens,
+            ignore_case: false,
+        }
+    }
+
+    /// Sets whether strings are matched ignoring ASCII case, so that
+    /// `"True"` and `"TRUE"` match `"true"`.
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    pub(crate) fn matches(&self, s: &str) -> bool {
+        self.tokens.iter().any(|token| {
+            if self.ignore_case {
+                token.eq_ignore_ascii_case(s)
+            } else {
+                token == s
+            }
+        })
+    }
+}
+
+impl Default for BooleanStrings {
+    fn default() -> Self {
+        Self::new(
+            ["true", "false", "yes", "no"]
+                .iter()
+                .map(|token| token.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// A set of paths to parts of the input, each associated with a value, that
+/// are subject to a hint in [`Hints`].
+///
+/// Paths work the same way as they do in [`HintSet`], including the `-`
+/// wildcard. If more than one path points to the same part of the input, the
+/// first one wins.
+pub struct HintMap<'a, T> {
+    values: Vec<(&'a [String], T)>,
+}
+
+impl<'a, T: Clone> HintMap<'a, T> {
+    /// Constructs a new [`HintMap`].
+    ///
+    /// ```
+    /// use jtd::Type;
+    /// use jtd_infer::HintMap;
+    ///
+    /// let path = vec!["foo".to_string(), "bar".to_string()];
+    /// HintMap::new(vec![(&path[..], Type::String)]);
+    /// ```
+    pub fn new(values: Vec<(&'a [String], T)>) -> Self {
+        HintMap { values }
+    }
+
+    /// Constructs a new [`HintMap`] from paths that are already split into
+    /// segments, each with its value, as kept in a [`Config`][`crate::Config`].
+    /// See [`HintSet::from_paths`].
+    ///
+    /// ```
+    /// use jtd::Type;
+    /// use jtd_infer::HintMap;
+    ///
+    /// let hints = vec![(vec!["foo".to_string(), "bar".to_string()], Type::String)];
+    /// HintMap::from_paths(&hints);
+    /// ```
+    pub fn from_paths(values: &'a [(Vec<String>, T)]) -> Self {
+        Self::new(
+            values
+                .iter()
+                .map(|(path, value)| (&path[..], value.clone()))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn sub_hints(&self, key: &str) -> Self {
+        Self::new(
+            self.values
+                .iter()
+                .filter(|(values, _)| {
+                    values
+                        .first()
+                        .is_some_and(|first| segment_matches(first, key))
+                })
+                .map(|(values, value)| (&values[1..], value.clone()))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn active(&self) -> Option<&T> {
+        self.values
+            .iter()
+            .find(|(values, _)| values.is_empty())
+            .map(|(_, value)| value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,4 +1382,105 @@ mod tests {
             .sub_hints("e")
             .is_active());
     }
+
+    #[test]
+    fn hint_set_matches() {
+        let path1 = vec!["a".to_string(), "b".to_string()];
+        let path2 = vec!["-".to_string(), "c".to_string()];
+        let root = vec![];
+        let hint_set = HintSet::new(vec![&path1, &path2]);
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+
+        assert_eq!(
+            vec![&path1[..], &path2[..]],
+            hint_set.paths().collect::<Vec<_>>()
+        );
+
+        assert!(hint_set.matches(&path(&["a", "b"])));
+        assert!(!hint_set.matches(&path(&["a"])));
+        assert!(!hint_set.matches(&path(&["a", "b", "c"])));
+        assert!(!hint_set.matches(&path(&["a", "-"])));
+
+        // A wildcard matches property names and array elements alike.
+        assert!(hint_set.matches(&path(&["x", "c"])));
+        assert!(hint_set.matches(&path(&["-", "c"])));
+        assert!(!hint_set.matches(&root));
+
+        // Matching agrees with going down the set one key at a time.
+        for keys in [&["a", "b"][..], &["x", "c"], &["a", "c"], &["a", "-"]] {
+            let sub_hints = keys
+                .iter()
+                .fold(HintSet::new(hint_set.paths().collect()), |hints, key| {
+                    hints.sub_hints(key)
+                });
+            assert_eq!(sub_hints.is_active(), hint_set.matches(&path(keys)));
+        }
+
+        assert!(HintSet::new(vec![&root]).matches(&root));
+        assert!(!HintSet::new(vec![]).matches(&root));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn hint_set_pattern() {
+        let path1 = vec![r"{user_\d+}".to_string(), "score".to_string()];
+        let path2 = vec!["{[".to_string()];
+        let hint_set = HintSet::new(vec![&path1, &path2]);
+
+        assert!(hint_set
+            .sub_hints("user_123")
+            .sub_hints("score")
+            .is_active());
+        assert!(hint_set.sub_hints("user_4").sub_hints("score").is_active());
+        assert!(!hint_set.sub_hints("user_").sub_hints("score").is_active());
+        assert!(!hint_set
+            .sub_hints("x_user_1")
+            .sub_hints("score")
+            .is_active());
+        assert!(!hint_set.sub_hints("-").sub_hints("score").is_active());
+
+        // An invalid pattern is just a key like any other.
+        assert!(hint_set.sub_hints("{[").is_active());
+        assert!(!hint_set.sub_hints("[").is_active());
+
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+        assert!(hint_set.matches(&path(&["user_123", "score"])));
+        assert!(!hint_set.matches(&path(&["-", "score"])));
+    }
+
+    #[test]
+    fn from_paths() {
+        let paths = vec![
+            vec!["a".to_string(), "b/c".to_string()],
+            vec!["-".to_string(), "~d".to_string()],
+        ];
+        let hint_set = HintSet::from_paths(&paths);
+        assert_eq!(
+            vec![&paths[0][..], &paths[1][..]],
+            hint_set.paths().collect::<Vec<_>>()
+        );
+        assert!(hint_set.sub_hints("a").sub_hints("b/c").is_active());
+        assert!(hint_set.sub_hints("x").sub_hints("~d").is_active());
+        assert!(!hint_set.sub_hints("a").sub_hints("b").is_active());
+
+        let values = vec![(paths[0].clone(), 1), (paths[1].clone(), 2)];
+        let hint_map = HintMap::from_paths(&values);
+        assert_eq!(Some(&1), hint_map.sub_hints("a").sub_hints("b/c").active());
+        assert_eq!(Some(&2), hint_map.sub_hints("a").sub_hints("~d").active());
+    }
+
+    #[test]
+    fn hint_map() {
+        let path1 = vec!["a".to_string(), "b".to_string()];
+        let path2 = vec!["-".to_string(), "b".to_string()];
+        let hint_map = HintMap::new(vec![(&path1, 1), (&path2, 2)]);
+
+        assert_eq!(None, hint_map.active());
+        assert_eq!(None, hint_map.sub_hints("a").active());
+        assert_eq!(Some(&1), hint_map.sub_hints("a").sub_hints("b").active());
+        assert_eq!(Some(&2), hint_map.sub_hints("x").sub_hints("b").active());
+        assert_eq!(None, hint_map.sub_hints("x").sub_hints("c").active());
+    }
 }
@@ -0,0 +1,98 @@
+use crate::error::InferError;
+use crate::hints::Hints;
+use crate::inferred_schema::{InferredKind, InferredSchema};
+use crate::warning::Warning;
+use jtd::Schema;
+use serde_json::Value;
+
+/// What's been inferred from some examples, on its own, without the rest of
+/// what an [`Inferrer`][`crate::Inferrer`] keeps track of.
+///
+/// This is for building up an inference yourself, with control over which
+/// examples go into it, and then either converting it into a schema directly
+/// or handing it to an inferrer with
+/// [`Inferrer::with_inference`][`crate::Inferrer::with_inference`]. That way,
+/// an inferrer can be seeded with examples known ahead of time, while only the
+/// examples it's given itself are counted as its samples.
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::{Hints, Inference, InferredKind, Inferrer};
+///
+/// let hints = Hints::default();
+/// let seed = Inference::from_value(&json!({ "name": "Alice" }), &hints);
+/// assert_eq!(InferredKind::Object, seed.kind(&hints));
+///
+/// let inferrer = Inferrer::new(hints)
+///     .with_inference(seed)
+///     .infer(json!({ "name": "Bob", "age": 42 }));
+///
+/// assert_eq!(1, inferrer.num_samples());
+/// assert_eq!(
+///     json!({
+///         "properties": { "name": { "type": "string" } },
+///         "optionalProperties": { "age": { "type": "uint8" } },
+///     }),
+///     inferrer.into_json().unwrap(),
+/// );
+/// ```
+///
+/// What an `Inference` holds is deliberately opaque, and may change from one
+/// version of this crate to the next. Only the methods here are meant to be
+/// relied on. Give every method of one inference the same hints, just as an
+/// inferrer uses the same hints throughout.
+#[derive(Debug)]
+pub struct Inference(pub(crate) InferredSchema);
+
+impl Inference {
+    /// An inference from no examples at all. As a schema, it's whatever
+    /// [`Hints::with_empty_fallback`] says, which is the empty form by default.
+    pub fn unknown() -> Self {
+        Self(InferredSchema::Unknown)
+    }
+
+    /// An inference from `value` alone.
+    pub fn from_value(value: &Value, hints: &Hints) -> Self {
+        Self::unknown().infer(value.clone(), hints)
+    }
+
+    /// Updates the inference with `value`, in the same way as
+    /// [`Inferrer::infer`][`crate::Inferrer::infer`].
+    pub fn infer(self, value: Value, hints: &Hints) -> Self {
+        Self(self.0.infer(value, hints))
+    }
+
+    /// A rough description of what's been inferred, as returned by
+    /// [`Inferrer::type_at`][`crate::Inferrer::type_at`] for the root.
+    pub fn kind(&self, hints: &Hints) -> InferredKind {
+        self.0
+            .kind_at(hints, &[])
+            .expect("the root of an inference always has a kind")
+    }
+
+    /// Describes anything questionable about the examples inferred from, as
+    /// [`Inferrer::warnings`][`crate::Inferrer::warnings`] does, apart from
+    /// repeated keys, which only an inferrer keeps track of.
+    pub fn warnings(&self, hints: &Hints) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        self.0.warnings(hints, &mut Vec::new(), &mut warnings);
+        warnings
+    }
+
+    /// Converts the inference into a schema, in the same way as
+    /// [`Inferrer::into_schema`][`crate::Inferrer::into_schema`], and with the
+    /// same checks.
+    pub fn into_schema(self, hints: &Hints) -> Result<Schema, InferError> {
+        let schema = self.0.into_schema(hints)?;
+        schema.validate().map_err(InferError::InvalidSchema)?;
+
+        Ok(schema)
+    }
+}
+
+/// The same as [`Inference::unknown`].
+impl Default for Inference {
+    fn default() -> Self {
+        Self::unknown()
+    }
+}
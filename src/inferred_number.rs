@@ -1,71 +1,281 @@
+use crate::config::parse_type_name;
+use crate::error::{type_name, InferError};
 use jtd::Type;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Number, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
 #[derive(Debug)]
 pub struct InferredNumber {
-    min: f64,
-    max: f64,
+    /// The smallest and largest numbers seen, or `None` if every number seen
+    /// was NaN, or none were seen at all.
+    range: Option<(f64, f64)>,
     int: bool,
+    big: bool,
+    non_finite: bool,
+    histogram: Option<Histogram>,
 }
 
 impl InferredNumber {
     pub fn new() -> Self {
         Self {
-            min: f64::MAX,
-            max: f64::MIN,
+            range: None,
             int: true,
+            big: false,
+            non_finite: false,
+            histogram: None,
         }
     }
 
-    pub fn infer(&self, n: f64) -> Self {
+    pub fn with_histogram(mut self) -> Self {
+        self.histogram = Some(Histogram::default());
+        self
+    }
+
+    /// Updates the inference with `n`.
+    ///
+    /// Zero is zero, whatever its sign: `-0`, `-0.0`, `0`, and `0.0` are all
+    /// the same non-negative integer, and so none of them make a signed or
+    /// floating-point type necessary.
+    ///
+    /// JSON has no NaN or infinities, but a [`Value`] that didn't come from
+    /// parsing JSON might. Infinities are clamped to the largest `float64` of
+    /// the same sign, and NaN is left out of the range of numbers seen, but
+    /// either makes `float64` the only type that's inferred: see
+    /// [`InferredNumber::non_finite`].
+    pub fn infer(self, n: f64) -> Self {
+        if n.is_nan() {
+            return Self {
+                int: false,
+                non_finite: true,
+                ..self
+            };
+        }
+
+        let non_finite = self.non_finite || n.is_infinite();
+
+        // Adding zero turns -0.0 into 0.0, and leaves every other number as
+        // it was.
+        let n = n.clamp(f64::MIN, f64::MAX) + 0.0;
+
+        let range = match self.range {
+            Some((min, max)) => (min.min(n), max.max(n)),
+            None => (n, n),
+        };
+
         Self {
-            min: self.min.min(n),
-            max: self.max.max(n),
-            int: self.int && n.fract() == 0.0,
+            range: Some(range),
+            int: self.int && !non_finite && n.fract() == 0.0,
+            big: self.big,
+            non_finite,
+            histogram: self.histogram.map(|histogram| histogram.infer(n)),
         }
     }
 
-    pub fn into_type(&self, default: &NumType) -> Type {
+    /// Like [`InferredNumber::infer`], but also notes whether `n` is a big
+    /// integer: see [`InferredNumber::big`].
+    pub fn infer_number(self, n: &Number) -> Self {
+        let big = is_big_integer(n);
+        let inferred = self.infer(n.as_f64().unwrap());
+
+        Self {
+            big: inferred.big || big,
+            ..inferred
+        }
+    }
+
+    /// Whether any of the numbers seen was an integer too large for an `i64`
+    /// or a `u64`, which a `float64` can only approximate.
+    pub fn big(&self) -> bool {
+        self.big
+    }
+
+    /// Whether any of the numbers seen was NaN or infinite, which only a
+    /// `float64` is inferred for.
+    pub fn non_finite(&self) -> bool {
+        self.non_finite
+    }
+
+    /// The histogram of the numbers seen, as it should appear in a schema's
+    /// metadata, if histograms are being tracked.
+    pub fn histogram(&self) -> Option<Value> {
+        self.histogram.as_ref().map(Histogram::to_value)
+    }
+
+    pub fn to_type(&self, default: &NumType) -> Type {
         if self.contained_by(default) {
             return default.into_type();
         }
 
-        let types = [
-            NumType::Uint8,
-            NumType::Int8,
-            NumType::Uint16,
-            NumType::Int16,
-            NumType::Uint32,
-            NumType::Int32,
-        ];
-
-        for type_ in &types {
+        for type_ in &WIDENING {
             if self.contained_by(type_) {
                 return type_.into_type();
             }
         }
 
-        return NumType::Float64.into_type();
+        unreachable!("float64 contains every number")
+    }
+
+    /// The smallest and largest numbers seen, if they don't all fit in
+    /// `default`, and so would be inferred as a wider type. That's never the
+    /// case if the only numbers seen were NaN, since there's no range of
+    /// numbers to speak of.
+    pub fn exceeds(&self, default: &NumType) -> Option<(f64, f64)> {
+        self.range.filter(|_| !self.contained_by(default))
     }
 
-    fn contained_by(&self, type_: &NumType) -> bool {
+    pub fn contained_by(&self, type_: &NumType) -> bool {
+        if self.non_finite {
+            return *type_ == NumType::Float64;
+        }
+
         if !self.int && !type_.is_float() {
             return false;
         }
 
-        let (min, max) = type_.as_range();
-        min <= self.min && max >= self.max
+        let (type_min, type_max) = type_.as_range();
+        self.range
+            .map_or(true, |(min, max)| type_min <= min && type_max >= max)
+    }
+}
+
+/// Whether `n` has no fractional part, but is too large for serde_json to have
+/// held it as an `i64` or a `u64`.
+///
+/// Without serde_json's `arbitrary_precision` feature, such numbers are parsed
+/// as an `f64`, and so this can't tell `123456789012345678901234567890` apart
+/// from `1.23456789012345678901234567890e29`. Both are taken to be integers.
+fn is_big_integer(n: &Number) -> bool {
+    // 2^63. Smaller integers always fit in an i64 or a u64.
+    const MIN_BIG: f64 = 9223372036854775808.0;
+
+    n.as_i64().is_none()
+        && n.as_u64().is_none()
+        && n.as_f64()
+            .is_some_and(|f| f.fract() == 0.0 && f.abs() >= MIN_BIG)
+}
+
+/// `n` as an integer, if it's one that fits in an `i64`.
+///
+/// As in [`InferredNumber::infer`], numbers written with a fractional part of
+/// zero, such as `0.0` or `-0.0`, count as integers too.
+pub fn integer_value(n: &Number) -> Option<i64> {
+    n.as_i64().or_else(|| {
+        let f = n.as_f64()?;
+        let fits = f.fract() == 0.0 && i64::MIN as f64 <= f && f < i64::MAX as f64;
+        fits.then_some(f as i64)
+    })
+}
+
+/// The largest power of ten that gets its own histogram bucket. Everything of
+/// a greater magnitude goes into one final, unbounded, bucket.
+const MAX_HISTOGRAM_EXPONENT: i32 = 15;
+
+/// Counts of numbers, bucketed by order of magnitude.
+///
+/// Buckets are fixed ahead of time, rather than derived from the data, so that
+/// the memory a histogram uses doesn't depend on what it's seen. Bucket `0`
+/// holds numbers in `(-1, 1)`. Bucket `k` holds numbers in `[10^(k-1), 10^k)`,
+/// and bucket `-k` holds their negations.
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: BTreeMap<i32, usize>,
+}
+
+impl Histogram {
+    fn infer(mut self, n: f64) -> Self {
+        *self.buckets.entry(Self::bucket(n)).or_insert(0) += 1;
+        self
+    }
+
+    fn bucket(n: f64) -> i32 {
+        if n.abs() < 1.0 {
+            return 0;
+        }
+
+        let exponent = (n.abs().log10().floor() as i32).min(MAX_HISTOGRAM_EXPONENT);
+        (exponent + 1) * n.signum() as i32
+    }
+
+    fn to_value(&self) -> Value {
+        // The bound of bucket k furthest from zero, with the given sign.
+        let bound = |k: i32, sign: f64| {
+            if k > MAX_HISTOGRAM_EXPONENT {
+                Value::Null
+            } else {
+                json!(sign * 10f64.powi(k))
+            }
+        };
+
+        let buckets: Vec<_> = self
+            .buckets
+            .iter()
+            .map(|(&k, &count)| {
+                let (from, to) = match k {
+                    0 => (json!(-1.0), json!(1.0)),
+                    k if k > 0 => (bound(k - 1, 1.0), bound(k, 1.0)),
+                    k => (bound(-k, -1.0), bound(-k - 1, -1.0)),
+                };
+
+                json!({ "from": from, "to": to, "count": count })
+            })
+            .collect();
+
+        Value::Array(buckets)
     }
 }
 
 /// A type of number to infer by default.
 ///
-/// See [`Hints`][`crate::Hints`] for how this enum is used.
-#[derive(Clone)]
+/// See [`Hints`][`crate::Hints`] for how this enum is used. It's
+/// (de)serialized as the name of the corresponding type, such as `"uint8"`, and
+/// its default is [`NumType::Uint8`].
+///
+/// Number types are partially ordered by containment: one type is less than
+/// another if every number the first accepts, the second accepts too.
+/// [`NumType::widen_to_fit`] moves up this order:
+///
+/// ```text
+/// int8 ─────┐
+///           ├─ int16 ──┐
+/// uint8 ─┬──┘          ├─ int32 ──┐
+///        └─ uint16 ─┬──┘          ├─ float32 ── float64
+///                   └─ uint32 ────┘
+/// ```
+///
+/// Going right along a line widens a type. So `int8 < int16`, `uint8 <
+/// int16`, `uint8 < uint16`, `uint16 < int32`, every integer type is less than
+/// `float32`, and so on. Besides that, there are two edge cases:
+///
+/// * A signed type is never less than an unsigned one, since unsigned types
+///   have no negative numbers. `int8` and `uint8` can't be compared at all,
+///   and neither can `int16` and `uint16`, or `int32` and `uint32`.
+/// * Like JSON Typedef, this takes `float32` and `float64` to accept any
+///   number, even though an `f32` can't hold every 32-bit integer exactly.
+///   Since `float64` holds more of them exactly, it's the greater of the two.
+///
+/// ```
+/// use jtd_infer::NumType;
+///
+/// assert!(NumType::Uint8 < NumType::Int16);
+/// assert!(NumType::Int32 < NumType::Float32);
+/// assert_eq!(None, NumType::Int8.partial_cmp(&NumType::Uint8));
+///
+/// assert!(NumType::Uint8.can_represent(255.0));
+/// assert!(!NumType::Uint8.can_represent(-1.0));
+/// assert_eq!(NumType::Int16, NumType::Uint8.widen_to_fit(-1.0));
+/// assert_eq!(NumType::Float64, NumType::Uint8.widen_to_fit(0.5));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NumType {
     /// Corresponds to [`jtd::Type::Int8`].
     Int8,
 
     /// Corresponds to [`jtd::Type::Uint8`].
+    #[default]
     Uint8,
 
     /// Corresponds to [`jtd::Type::Int16`].
@@ -88,13 +298,83 @@ pub enum NumType {
 }
 
 impl NumType {
-    fn is_float(&self) -> bool {
-        match self {
-            Self::Float32 | Self::Float64 => true,
-            _ => false,
+    /// The name of the corresponding type, as it appears in a schema, such as
+    /// `"uint8"`. The reverse of parsing a number type with [`str::parse`].
+    ///
+    /// ```
+    /// use jtd_infer::NumType;
+    ///
+    /// assert_eq!("float32", NumType::Float32.as_jtd_str());
+    /// assert_eq!(Ok(NumType::Float32), "float32".parse());
+    /// assert!("string".parse::<NumType>().is_err());
+    /// ```
+    pub fn as_jtd_str(&self) -> &'static str {
+        type_name(&self.into_type())
+    }
+
+    /// The number type corresponding to a JSON Typedef type, if that type is
+    /// numeric.
+    pub(crate) fn from_type(type_: &Type) -> Option<Self> {
+        match type_ {
+            Type::Int8 => Some(Self::Int8),
+            Type::Uint8 => Some(Self::Uint8),
+            Type::Int16 => Some(Self::Int16),
+            Type::Uint16 => Some(Self::Uint16),
+            Type::Int32 => Some(Self::Int32),
+            Type::Uint32 => Some(Self::Uint32),
+            Type::Float32 => Some(Self::Float32),
+            Type::Float64 => Some(Self::Float64),
+            Type::Boolean | Type::String | Type::Timestamp => None,
+        }
+    }
+
+    /// Whether a schema of this type accepts `n`.
+    ///
+    /// Integer types accept integers within their range. Floating-point types
+    /// accept any number, as in JSON Typedef.
+    pub fn can_represent(&self, n: f64) -> bool {
+        let (min, max) = self.as_range();
+        (self.is_float() || n.fract() == 0.0) && min <= n && n <= max
+    }
+
+    /// The narrowest type that accepts both everything this type does and
+    /// `n`.
+    ///
+    /// This is `self` if it already accepts `n`. Otherwise, it's the first of
+    /// `uint8`, `int8`, `uint16`, `int16`, `uint32`, `int32`, and `float64`
+    /// that's greater than `self` and accepts `n`. As when inferring numbers,
+    /// `float32` is never widened to, since it accepts no more numbers than
+    /// `float64` does, and holds fewer of them exactly.
+    pub fn widen_to_fit(self, n: f64) -> Self {
+        if self.can_represent(n) {
+            return self;
+        }
+
+        WIDENING
+            .iter()
+            .copied()
+            .find(|type_| self < *type_ && type_.can_represent(n))
+            .unwrap_or(Self::Float64)
+    }
+
+    /// Whether every number that `other` accepts, this type accepts too.
+    fn contains(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Float64, _) => true,
+            (Self::Float32, other) => *other != Self::Float64,
+            (_, other) if other.is_float() => false,
+            _ => {
+                let (min, max) = self.as_range();
+                let (other_min, other_max) = other.as_range();
+                min <= other_min && other_max <= max
+            }
         }
     }
 
+    fn is_float(&self) -> bool {
+        matches!(self, Self::Float32 | Self::Float64)
+    }
+
     fn as_range(&self) -> (f64, f64) {
         match self {
             Self::Int8 => (i8::MIN as f64, i8::MAX as f64),
@@ -107,7 +387,7 @@ impl NumType {
         }
     }
 
-    fn into_type(&self) -> Type {
+    pub(crate) fn into_type(self) -> Type {
         match self {
             Self::Int8 => Type::Int8,
             Self::Uint8 => Type::Uint8,
@@ -121,6 +401,45 @@ impl NumType {
     }
 }
 
+/// The types that numbers are widened to, narrowest first.
+const WIDENING: [NumType; 7] = [
+    NumType::Uint8,
+    NumType::Int8,
+    NumType::Uint16,
+    NumType::Int16,
+    NumType::Uint32,
+    NumType::Int32,
+    NumType::Float64,
+];
+
+/// Parses the name of a number type, as it appears in a schema, such as
+/// `"uint8"`. Any other name is an [`InferError::UnknownNumType`].
+impl FromStr for NumType {
+    type Err = InferError;
+
+    fn from_str(name: &str) -> Result<Self, InferError> {
+        parse_type_name(name)
+            .as_ref()
+            .and_then(Self::from_type)
+            .ok_or_else(|| InferError::UnknownNumType(name.to_owned()))
+    }
+}
+
+/// Orders number types by containment. See [`NumType`].
+impl PartialOrd for NumType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if other.contains(self) {
+            Some(Ordering::Less)
+        } else if self.contains(other) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,52 +449,216 @@ mod tests {
         let n = InferredNumber::new();
 
         // At first, default always honored.
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Uint16, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Uint32, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        assert_eq!(Type::Uint8, n.to_type(&NumType::Uint8));
+        assert_eq!(Type::Int8, n.to_type(&NumType::Int8));
+        assert_eq!(Type::Uint16, n.to_type(&NumType::Uint16));
+        assert_eq!(Type::Int16, n.to_type(&NumType::Int16));
+        assert_eq!(Type::Uint32, n.to_type(&NumType::Uint32));
+        assert_eq!(Type::Int32, n.to_type(&NumType::Int32));
+        assert_eq!(Type::Float32, n.to_type(&NumType::Float32));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Float64));
 
         // Test expanding to limits of uint8.
         let n = InferredNumber::new()
             .infer(u8::MIN as f64)
             .infer(u8::MAX as f64);
 
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Uint16, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Uint32, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        assert_eq!(Type::Uint8, n.to_type(&NumType::Uint8));
+        assert_eq!(Type::Uint8, n.to_type(&NumType::Int8));
+        assert_eq!(Type::Uint16, n.to_type(&NumType::Uint16));
+        assert_eq!(Type::Int16, n.to_type(&NumType::Int16));
+        assert_eq!(Type::Uint32, n.to_type(&NumType::Uint32));
+        assert_eq!(Type::Int32, n.to_type(&NumType::Int32));
+        assert_eq!(Type::Float32, n.to_type(&NumType::Float32));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Float64));
 
         // Test expanding to limits of int8.
         let n = InferredNumber::new()
             .infer(i8::MIN as f64)
             .infer(i8::MAX as f64);
 
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        assert_eq!(Type::Int8, n.to_type(&NumType::Uint8));
+        assert_eq!(Type::Int8, n.to_type(&NumType::Int8));
+        assert_eq!(Type::Int8, n.to_type(&NumType::Uint16));
+        assert_eq!(Type::Int16, n.to_type(&NumType::Int16));
+        assert_eq!(Type::Int8, n.to_type(&NumType::Uint32));
+        assert_eq!(Type::Int32, n.to_type(&NumType::Int32));
+        assert_eq!(Type::Float32, n.to_type(&NumType::Float32));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Float64));
 
         // Test including a non-integer.
         let n = InferredNumber::new().infer(0.5);
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint8));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Int8));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint16));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Int16));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint32));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Int32));
+        assert_eq!(Type::Float32, n.to_type(&NumType::Float32));
+        assert_eq!(Type::Float64, n.to_type(&NumType::Float64));
+    }
+
+    #[test]
+    fn big_integers() {
+        let big: Number = serde_json::from_str("123456789012345678901234567890").unwrap();
+        let n = InferredNumber::new().infer_number(&big);
+        assert!(n.big());
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint8));
+
+        let n = n.infer_number(&Number::from(1));
+        assert!(n.big());
+
+        // The largest 64-bit integers aren't big.
+        for n in [Number::from(u64::MAX), Number::from(i64::MIN)] {
+            assert!(!InferredNumber::new().infer_number(&n).big(), "{}", n);
+        }
+
+        let n: Number = serde_json::from_str("-123456789012345678901234567890").unwrap();
+        assert!(InferredNumber::new().infer_number(&n).big());
+
+        let n: Number = serde_json::from_str("1.5e30").unwrap();
+        assert!(InferredNumber::new().infer_number(&n).big());
+
+        let n = Number::from_f64(0.5).unwrap();
+        assert!(!InferredNumber::new().infer_number(&n).big());
+    }
+
+    #[test]
+    fn negative_zero() {
+        // Every way of writing zero is a non-negative integer.
+        for zero in [0.0, -0.0] {
+            let n = InferredNumber::new().infer(zero).infer(1.0);
+            assert_eq!(Type::Uint8, n.to_type(&NumType::Uint8));
+        }
+
+        for zero in ["0", "-0", "0.0", "-0.0"] {
+            let zero: Number = serde_json::from_str(zero).unwrap();
+            assert_eq!(Some(0), integer_value(&zero));
+        }
+
+        // Only a fractional number makes for a float, and -0.0 still doesn't
+        // make it signed.
+        let n = InferredNumber::new().infer(-0.0).infer(0.5);
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint8));
+
+        let (min, _) = n.exceeds(&NumType::Uint8).unwrap();
+        assert!(min == 0.0 && min.is_sign_positive());
+
+        assert_eq!(None, integer_value(&Number::from_f64(0.5).unwrap()));
+        assert_eq!(None, integer_value(&Number::from(u64::MAX)));
+        assert_eq!(Some(-3), integer_value(&Number::from_f64(-3.0).unwrap()));
+    }
+
+    #[test]
+    fn non_finite() {
+        for n in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let inferred = InferredNumber::new().infer(1.0).infer(n);
+            assert!(inferred.non_finite());
+            for default in &WIDENING {
+                assert_eq!(Type::Float64, inferred.to_type(default), "{}", n);
+            }
+        }
+
+        // Infinities are clamped, and NaN leaves the range as it was.
+        let n = InferredNumber::new()
+            .infer(f64::NEG_INFINITY)
+            .infer(f64::INFINITY);
+        assert_eq!(Some((f64::MIN, f64::MAX)), n.exceeds(&NumType::Float32));
+
+        let n = InferredNumber::new().infer(2.0).infer(f64::NAN).infer(3.0);
+        assert_eq!(Some((2.0, 3.0)), n.exceeds(&NumType::Uint8));
+
+        let n = InferredNumber::new().infer(2.0).infer(3.0);
+        assert!(!n.non_finite());
+
+        // With only NaN, there's no range to report.
+        let n = InferredNumber::new().infer(f64::NAN).infer(f64::NAN);
+        assert_eq!(Type::Float64, n.to_type(&NumType::Uint8));
+        assert_eq!(None, n.exceeds(&NumType::Uint8));
+    }
+
+    #[test]
+    fn names() {
+        for num_type in WIDENING.iter().chain(&[NumType::Float32]) {
+            assert_eq!(Ok(*num_type), num_type.as_jtd_str().parse());
+        }
+
+        assert_eq!(
+            Err(InferError::UnknownNumType("int64".to_owned())),
+            "int64".parse::<NumType>()
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        use NumType::*;
+
+        let all = [Int8, Uint8, Int16, Uint16, Int32, Uint32, Float32, Float64];
+        for a in all {
+            for b in all {
+                // The order agrees with which numbers each type accepts, as
+                // far as the edges of their ranges go.
+                let (min, max) = a.as_range();
+                let contains = b.can_represent(min) && b.can_represent(max);
+                if a <= b {
+                    assert!(contains, "{:?} <= {:?}", a, b);
+                }
+
+                if a < b {
+                    assert_eq!(Some(Ordering::Greater), b.partial_cmp(&a));
+                }
+            }
+        }
+
+        assert!(Uint16 < Int32);
+        assert!(Uint32 < Float32);
+        assert!(Float32 < Float64);
+        assert_eq!(None, Uint32.partial_cmp(&Int32));
+        assert_eq!(None, Int16.partial_cmp(&Uint16));
+    }
+
+    #[test]
+    fn widen_to_fit() {
+        use NumType::*;
+
+        assert_eq!(Uint8, Uint8.widen_to_fit(7.0));
+        assert_eq!(Uint16, Uint8.widen_to_fit(256.0));
+        assert_eq!(Int16, Uint8.widen_to_fit(-1.0));
+        assert_eq!(Int16, Int8.widen_to_fit(128.0));
+        assert_eq!(Int32, Uint16.widen_to_fit(-1.0));
+        assert_eq!(Float64, Uint32.widen_to_fit(-1.0));
+        assert_eq!(Float64, Int32.widen_to_fit(0.5));
+        assert_eq!(Float32, Float32.widen_to_fit(1e300));
+
+        assert!(Int8.can_represent(-128.0));
+        assert!(!Int8.can_represent(128.0));
+        assert!(!Uint32.can_represent(1.5));
+        assert!(Float32.can_represent(1.5));
+    }
+
+    #[test]
+    fn histogram() {
+        assert_eq!(None, InferredNumber::new().infer(1.0).histogram());
+
+        let n = InferredNumber::new()
+            .with_histogram()
+            .infer(0.5)
+            .infer(-0.5)
+            .infer(3.0)
+            .infer(7.0)
+            .infer(42.0)
+            .infer(-150.0)
+            .infer(1e20);
+
+        assert_eq!(
+            Some(json!([
+                { "from": -1000.0, "to": -100.0, "count": 1 },
+                { "from": -1.0, "to": 1.0, "count": 2 },
+                { "from": 1.0, "to": 10.0, "count": 2 },
+                { "from": 10.0, "to": 100.0, "count": 1 },
+                { "from": 1e15, "to": null, "count": 1 },
+            ])),
+            n.histogram()
+        );
     }
 }
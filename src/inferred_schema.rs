@@ -1,46 +1,131 @@
-use crate::hints::Hints;
-use crate::inferred_number::InferredNumber;
-use chrono::DateTime;
+use crate::describe::describe_properties;
+use crate::error::InferError;
+use crate::format::is_timestamp;
+use crate::hints::{BigIntegers, EmptyFallback, Hints, MixedScalars, NullableFallback, WILDCARD};
+use crate::inferred_number::{integer_value, InferredNumber, NumType};
+use crate::reservoir::Reservoir;
+use crate::warning::Warning;
 use jtd::{Schema, Type};
-use serde_json::Value;
-use std::collections::{BTreeMap, BTreeSet};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A rough description of what was inferred at some part of the input, as
+/// returned by [`Inferrer::type_at`][`crate::Inferrer::type_at`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InferredKind {
+    /// Nothing was seen here yet, such as the elements of arrays that were
+    /// always empty.
+    Unknown,
+
+    /// A mix of kinds, which only the empty form accepts.
+    Any,
+    Boolean,
+    Number,
+    String,
+    Timestamp,
+    Enum,
+    Array,
+
+    /// Objects, described property by property.
+    Object,
+
+    /// Objects, described as maps from keys to values under a values hint.
+    Values,
+
+    /// Objects, described as a discriminator with a mapping for each tag.
+    Discriminator,
+
+    /// Whatever's within, or nulls as well. `Nullable(Unknown)` is a part of
+    /// the input that was only ever null.
+    Nullable(Box<InferredKind>),
+}
 
 #[derive(Debug)]
 pub enum InferredSchema {
     Unknown,
-    Any,
+
+    /// Examples of kinds that no one schema but the empty form accepts.
+    /// `kinds` describes every kind seen, as in [`Warning::TypeConflict`].
+    Any {
+        kinds: BTreeSet<&'static str>,
+    },
     Boolean,
     Number(InferredNumber),
     String,
-    Timestamp,
+
+    /// A string whose format is yet to be detected. This is what strings are
+    /// inferred as unless an enum hint applies.
+    ///
+    /// `timestamps` is whether every string so far was a timestamp, checked as
+    /// each one is seen. `samples` is a sample of the strings, for the format
+    /// detectors to go over once the schema is made.
+    SampledString {
+        timestamps: bool,
+        samples: Reservoir,
+    },
+
     Enum(BTreeSet<String>),
-    Array(Box<InferredSchema>),
+
+    /// Integers collected to be emitted as an enum, with how many times each
+    /// was seen.
+    NumericEnum(BTreeMap<i64, usize>),
+
+    /// `elements` is what every element was inferred as. Under the tuples
+    /// hint, `tuple` also has what the elements at each index were inferred
+    /// as, for as long as every array has been the same length.
+    Array {
+        elements: Box<InferredSchema>,
+        tuple: Option<Vec<InferredSchema>>,
+    },
+    /// `objects` is how many objects were seen here, which is only ever as
+    /// many as the objects that this one was within.
     Properties {
         required: BTreeMap<String, InferredSchema>,
         optional: BTreeMap<String, InferredSchema>,
+        objects: usize,
     },
     Values(Box<InferredSchema>),
+    /// `untagged` is how many objects didn't have the tag as a string, and so
+    /// weren't inferred into any of the `mapping`.
     Discriminator {
         discriminator: String,
         mapping: BTreeMap<String, InferredSchema>,
+        untagged: usize,
+    },
+
+    /// The objects among the elements of an array, while it isn't yet known
+    /// whether they share a tag. `merged` is what they're inferred as without
+    /// one. `tagged` has, for each property that could still be the tag, the
+    /// mapping they're inferred as with it as the discriminator.
+    TaggedElements {
+        merged: Box<InferredSchema>,
+        tagged: BTreeMap<String, BTreeMap<String, InferredSchema>>,
     },
     Nullable(Box<InferredSchema>),
 }
 
 impl InferredSchema {
     pub fn infer(self, value: Value, hints: &Hints) -> Self {
+        // Nothing is kept track of for a part of the input that's hinted to
+        // accept anything.
+        if hints.is_any_active() {
+            return InferredSchema::Unknown;
+        }
+
         match (self, value) {
-            // Handle all null-related cases first. After these two branches,
+            // Handle all null-related cases first. After these three branches,
             // neither the current inference nor the incoming data will be null.
             //
-            // This will cause a deep tree of Nullable when dealing with a long
-            // sequence of nulls.
+            // An inference that is already Nullable is not wrapped again, so
+            // that a long sequence of nulls doesn't build up a deep tree of
+            // Nullable.
             //
-            // If this proves to be a performance concern, we may want to check
-            // if the sub-inference is Nullable, and avoid wrapping in that
-            // case.
-            (sub_infer @ _, Value::Null) => InferredSchema::Nullable(Box::new(sub_infer)),
-            (InferredSchema::Nullable(sub_infer), value @ _) => {
+            // These branches apply at any position, including the elements of
+            // an array and the values of a values form, so those can be
+            // nullable independently of their container.
+            (sub_infer @ InferredSchema::Nullable(_), Value::Null) => sub_infer,
+            (sub_infer, Value::Null) => InferredSchema::Nullable(Box::new(sub_infer)),
+            (InferredSchema::Nullable(sub_infer), value) => {
                 InferredSchema::Nullable(Box::new(sub_infer.infer(value, hints)))
             }
 
@@ -51,122 +136,191 @@ impl InferredSchema {
             // particular form.
             (InferredSchema::Unknown, Value::Bool(_)) => InferredSchema::Boolean,
             (InferredSchema::Unknown, Value::Number(n)) => {
-                InferredSchema::Number(InferredNumber::new().infer(n.as_f64().unwrap()))
+                if hints.numeric_enum_size().is_some() {
+                    InferredSchema::NumericEnum(BTreeMap::new()).infer(Value::Number(n), hints)
+                } else {
+                    InferredSchema::Number(new_number(hints).infer_number(&n))
+                }
             }
             (InferredSchema::Unknown, Value::String(s)) => {
                 if hints.is_enum_active() {
-                    let mut values = BTreeSet::new();
-                    values.insert(s);
-
-                    InferredSchema::Enum(values)
-                } else if DateTime::parse_from_rfc3339(&s).is_ok() {
-                    InferredSchema::Timestamp
+                    InferredSchema::Enum(BTreeSet::new()).infer(Value::String(s), hints)
                 } else {
-                    InferredSchema::String
+                    let samples = Reservoir::new(hints.format_sample_size());
+                    InferredSchema::SampledString {
+                        timestamps: true,
+                        samples,
+                    }
+                    .infer(Value::String(s), hints)
                 }
             }
             (InferredSchema::Unknown, Value::Array(vals)) => {
-                let mut sub_infer = InferredSchema::Unknown;
-                for (i, v) in vals.into_iter().enumerate() {
-                    sub_infer = sub_infer.infer(v, &hints.sub_hints(&i.to_string()));
-                }
+                let tuple = hints.tuples().then(|| {
+                    vals.iter()
+                        .map(|_| InferredSchema::Unknown)
+                        .collect::<Vec<_>>()
+                });
 
-                InferredSchema::Array(Box::new(sub_infer))
+                InferredSchema::Array {
+                    elements: Box::new(InferredSchema::Unknown),
+                    tuple,
+                }
+                .infer(Value::Array(vals), hints)
             }
-            (InferredSchema::Unknown, Value::Object(mut obj)) => {
+            (InferredSchema::Unknown, Value::Object(obj)) => {
                 if hints.is_values_active() {
                     let mut sub_infer = InferredSchema::Unknown;
                     for (k, v) in obj {
-                        sub_infer = sub_infer.infer(v, &hints.sub_hints(&k));
+                        sub_infer = sub_infer.infer(v, &hints.values_sub_hints(&k));
                     }
 
                     return InferredSchema::Values(Box::new(sub_infer));
                 }
 
                 if let Some(discriminator) = hints.peek_active_discriminator() {
-                    if let Some(Value::String(mapping_key)) = obj.remove(discriminator) {
-                        let infer_rest = InferredSchema::Unknown.infer(Value::Object(obj), hints);
-
-                        let mut mapping = BTreeMap::new();
-                        mapping.insert(mapping_key.to_owned(), infer_rest);
-
-                        return InferredSchema::Discriminator {
-                            discriminator: discriminator.to_owned(),
-                            mapping,
-                        };
+                    return InferredSchema::Discriminator {
+                        discriminator: discriminator.to_owned(),
+                        mapping: BTreeMap::new(),
+                        untagged: 0,
                     }
+                    .infer(Value::Object(obj), hints);
                 }
 
-                let mut props = BTreeMap::new();
-                for (k, v) in obj {
-                    let sub_infer = InferredSchema::Unknown.infer(v, &hints.sub_hints(&k));
-                    props.insert(k, sub_infer);
-                }
-
-                InferredSchema::Properties {
-                    required: props,
-                    optional: BTreeMap::new(),
-                }
+                infer_properties(obj, hints)
             }
 
             // Handle updating an inferred "any". Sort of a trivial case; once
             // we've inferred something can be "anything", we'll never narrow it
             // down thereafter.
-            (InferredSchema::Any, _) => InferredSchema::Any,
+            //
+            // Every kind of example is kept track of, so that the warning about
+            // it is the same whichever order the examples came in.
+            (InferredSchema::Any { mut kinds }, value) => {
+                kinds.insert(describe_value(&value));
+                InferredSchema::Any { kinds }
+            }
+
+            // For each kind of scalar below, data of any other kind is handed
+            // to mixed_scalars, which decides what the position becomes
+            // according to the hints.
 
             // Handle updating an inferred boolean primitive.
             (InferredSchema::Boolean, Value::Bool(_)) => InferredSchema::Boolean,
-            (InferredSchema::Boolean, _) => InferredSchema::Any,
+            (prior @ InferredSchema::Boolean, value) => mixed_scalars(&prior, &value, hints),
 
             // Handle updating an inferred number primitive.
             (InferredSchema::Number(inferred_number), Value::Number(n)) => {
-                InferredSchema::Number(inferred_number.infer(n.as_f64().unwrap()))
+                InferredSchema::Number(inferred_number.infer_number(&n))
             }
-            (InferredSchema::Number(_), _) => InferredSchema::Any,
+            (prior @ InferredSchema::Number(_), value) => mixed_scalars(&prior, &value, hints),
 
-            // Handle updating an inferred timestamp primitive.
-            (InferredSchema::Timestamp, Value::String(s)) => {
-                if DateTime::parse_from_rfc3339(&s).is_ok() {
-                    InferredSchema::Timestamp
-                } else {
-                    InferredSchema::String
+            // Handle updating a string whose format is yet to be detected,
+            // timestamps included.
+            (
+                InferredSchema::SampledString {
+                    timestamps,
+                    mut samples,
+                },
+                Value::String(s),
+            ) => {
+                let timestamps = timestamps && is_timestamp(&s);
+                samples.insert(s);
+                InferredSchema::SampledString {
+                    timestamps,
+                    samples,
                 }
             }
-            (InferredSchema::Timestamp, _) => InferredSchema::Any,
+            (prior @ InferredSchema::SampledString { .. }, value) => {
+                mixed_scalars(&prior, &value, hints)
+            }
 
             // Handle updating an inferred string primitive.
             (InferredSchema::String, Value::String(_)) => InferredSchema::String,
-            (InferredSchema::String, _) => InferredSchema::Any,
+            (prior @ InferredSchema::String, value) => mixed_scalars(&prior, &value, hints),
 
             // Handle updating an inferred enum.
+            //
+            // If the enum grows past the configured maximum size, the values
+            // collected so far are dropped right away, so that high-cardinality
+            // data can't make the set grow without bound.
             (InferredSchema::Enum(mut values), Value::String(s)) => {
                 values.insert(s);
-                InferredSchema::Enum(values)
+
+                match hints.max_enum_size() {
+                    Some(max_enum_size) if values.len() > max_enum_size => InferredSchema::String,
+                    _ => InferredSchema::Enum(values),
+                }
+            }
+            (prior @ InferredSchema::Enum(_), value) => mixed_scalars(&prior, &value, hints),
+
+            // Handle updating an inferred numeric enum.
+            //
+            // JSON Typedef enums can only hold strings, and so only integers
+            // are collected, to be emitted as strings later. If a number that
+            // isn't an integer comes along, or the enum grows too large, the
+            // values seen so far are folded into an ordinary number inference.
+            (InferredSchema::NumericEnum(mut values), Value::Number(n)) => {
+                let max_size = hints.numeric_enum_size().flatten();
+                let fits = integer_value(&n).filter(|i| {
+                    values.contains_key(i)
+                        || max_size.map_or(true, |max_size| values.len() < max_size)
+                });
+
+                if let Some(i) = fits {
+                    *values.entry(i).or_insert(0) += 1;
+                    InferredSchema::NumericEnum(values)
+                } else {
+                    let inferred_number = numbers_of(&values, new_number(hints));
+                    InferredSchema::Number(inferred_number.infer_number(&n))
+                }
             }
-            (InferredSchema::Enum(_), _) => InferredSchema::Any,
+            (prior @ InferredSchema::NumericEnum(_), value) => mixed_scalars(&prior, &value, hints),
 
             // Handle updating an inferred array.
-            (InferredSchema::Array(prior), Value::Array(vals)) => {
-                let mut sub_infer = *prior;
+            (InferredSchema::Array { elements, tuple }, Value::Array(vals)) => {
+                // Once an array is a different length from the ones before,
+                // they're no longer kept track of index by index.
+                let tuple = tuple
+                    .filter(|tuple| tuple.len() == vals.len())
+                    .map(|tuple| {
+                        tuple
+                            .into_iter()
+                            .zip(&vals)
+                            .enumerate()
+                            .map(|(i, (sub_infer, v))| {
+                                sub_infer.infer(v.clone(), &hints.sub_hints(&i.to_string()))
+                            })
+                            .collect()
+                    });
+
+                let mut sub_infer = *elements;
                 for (i, v) in vals.into_iter().enumerate() {
-                    sub_infer = sub_infer.infer(v, &hints.sub_hints(&i.to_string()));
+                    sub_infer = infer_element(sub_infer, v, &hints.sub_hints(&i.to_string()));
                 }
 
-                InferredSchema::Array(Box::new(sub_infer))
+                InferredSchema::Array {
+                    elements: Box::new(sub_infer),
+                    tuple,
+                }
+            }
+            (prior @ InferredSchema::Array { .. }, value) => {
+                InferredSchema::conflict(&prior, &value)
             }
-            (InferredSchema::Array(_), _) => InferredSchema::Any,
 
             // Handle updating an inferred properties form.
             (
                 InferredSchema::Properties {
                     mut required,
                     mut optional,
+                    objects,
                 },
                 Value::Object(map),
             ) => {
+                let entries = canonical_entries(map, required.keys().chain(optional.keys()), hints);
+                let present_keys: BTreeSet<_> = entries.iter().map(|(k, _)| k).collect();
                 let missing_required_keys: Vec<_> = required
                     .keys()
-                    .filter(|k| !map.contains_key(k.clone()))
+                    .filter(|k| !present_keys.contains(k))
                     .cloned()
                     .collect();
 
@@ -175,7 +329,7 @@ impl InferredSchema {
                     optional.insert(k, sub_infer);
                 }
 
-                for (k, v) in map {
+                for (k, v) in entries {
                     if required.contains_key(&k) {
                         let sub_infer = required.remove(&k).unwrap().infer(v, &hints.sub_hints(&k));
                         required.insert(k, sub_infer);
@@ -188,136 +342,393 @@ impl InferredSchema {
                     }
                 }
 
-                InferredSchema::Properties { required, optional }
+                InferredSchema::Properties {
+                    required,
+                    optional,
+                    objects: objects + 1,
+                }
+            }
+            (prior @ InferredSchema::Properties { .. }, value) => {
+                InferredSchema::conflict(&prior, &value)
             }
-            (InferredSchema::Properties { .. }, _) => InferredSchema::Any,
 
             // Handle updating an inferred values form.
             (InferredSchema::Values(prior), Value::Object(map)) => {
                 let mut sub_infer = *prior;
                 for (k, v) in map {
-                    sub_infer = InferredSchema::Unknown.infer(v, &hints.sub_hints(&k));
+                    sub_infer = sub_infer.infer(v, &hints.values_sub_hints(&k));
                 }
 
-                return InferredSchema::Values(Box::new(sub_infer));
+                InferredSchema::Values(Box::new(sub_infer))
             }
-            (InferredSchema::Values(_), _) => InferredSchema::Any,
+            (prior @ InferredSchema::Values(_), value) => InferredSchema::conflict(&prior, &value),
 
             // Handle updating an inferred discriminator form.
+            //
+            // An object without the tag as a string can't be in the mapping.
+            // It's counted, so that converting to a schema can fail with how
+            // many there were.
             (
                 InferredSchema::Discriminator {
                     discriminator,
                     mut mapping,
+                    untagged,
                 },
                 Value::Object(mut obj),
-            ) => {
-                let mapping_key = obj.remove(&discriminator);
-                if let Some(Value::String(mapping_key_str)) = mapping_key {
-                    if !mapping.contains_key(&mapping_key_str) {
-                        mapping.insert(mapping_key_str.clone(), InferredSchema::Unknown);
-                    }
-
-                    let sub_infer = mapping
-                        .remove(&mapping_key_str)
-                        .unwrap()
-                        .infer(Value::Object(obj), hints);
-                    mapping.insert(mapping_key_str, sub_infer);
+            ) => match obj.remove(&discriminator) {
+                Some(Value::String(mapping_key)) => {
+                    // The rest of the object is never itself a discriminator,
+                    // even though the hint still points at the tag.
+                    let sub_infer = match mapping.remove(&mapping_key) {
+                        Some(prior) => prior.infer(Value::Object(obj), hints),
+                        None => infer_properties(obj, hints),
+                    };
+                    mapping.insert(mapping_key, sub_infer);
 
                     InferredSchema::Discriminator {
                         discriminator,
                         mapping,
+                        untagged,
                     }
-                } else {
-                    InferredSchema::Any
+                }
+                _ => InferredSchema::Discriminator {
+                    discriminator,
+                    mapping,
+                    untagged: untagged + 1,
+                },
+            },
+            (prior @ InferredSchema::Discriminator { .. }, value) => {
+                InferredSchema::conflict(&prior, &value)
+            }
+
+            // Handle updating objects that may share a tag.
+            //
+            // A property stops being a candidate for the tag as soon as an
+            // object doesn't have it as a string, or gives it one value too
+            // many. Anything but an object leaves only the merged inference.
+            (InferredSchema::TaggedElements { merged, mut tagged }, Value::Object(obj)) => {
+                let max_tags = hints.max_element_tags().unwrap_or(0);
+                tagged.retain(|tag, mapping| match obj.get(tag) {
+                    Some(Value::String(s)) => mapping.contains_key(s) || mapping.len() < max_tags,
+                    _ => false,
+                });
+
+                for (tag, mapping) in &mut tagged {
+                    let mut rest = obj.clone();
+                    let mapping_key = match rest.remove(tag) {
+                        Some(Value::String(mapping_key)) => mapping_key,
+                        _ => unreachable!("candidate tag isn't a string"),
+                    };
+
+                    let sub_infer = mapping
+                        .remove(&mapping_key)
+                        .unwrap_or(InferredSchema::Unknown)
+                        .infer(Value::Object(rest), hints);
+                    mapping.insert(mapping_key, sub_infer);
+                }
+
+                InferredSchema::TaggedElements {
+                    merged: Box::new(merged.infer(Value::Object(obj), hints)),
+                    tagged,
                 }
             }
-            (InferredSchema::Discriminator { .. }, _) => InferredSchema::Any,
+            (InferredSchema::TaggedElements { merged, .. }, value) => merged.infer(value, hints),
         }
     }
 
-    pub fn into_schema(self, hints: &Hints) -> Schema {
-        match self {
-            InferredSchema::Unknown | InferredSchema::Any => Schema::Empty {
+    /// Converts the inference into a schema.
+    ///
+    /// Hints are followed down the same paths as in [`InferredSchema::infer`].
+    /// Since by this point array elements and the values of a values form have
+    /// been merged together, the hints for those are the ones that apply to
+    /// any element or value, rather than a specific one.
+    ///
+    /// This fails if a type hint is active for a part of the inference whose
+    /// examples the hinted type wouldn't accept.
+    pub fn into_schema(self, hints: &Hints) -> Result<Schema, InferError> {
+        if hints.is_any_active() {
+            return Ok(Schema::Empty {
                 definitions: Default::default(),
                 metadata: Default::default(),
+            });
+        }
+
+        // A discriminator hint points to the tag within objects. One that
+        // points to objects themselves has no tag to go on.
+        if hints.is_discriminator_untagged() {
+            if let InferredSchema::Properties { .. }
+            | InferredSchema::Values(_)
+            | InferredSchema::Discriminator { .. }
+            | InferredSchema::TaggedElements { .. } = self
+            {
+                return Err(InferError::DiscriminatorHintWithoutTag { path: vec![] });
+            }
+        }
+
+        if let Some(type_) = hints.active_type() {
+            return self.into_type_schema(type_.clone(), hints);
+        }
+
+        // A nullable inference is checked once its nulls are set aside, as the
+        // inference within goes through here too.
+        if hints.is_enum_active() {
+            if let InferredSchema::Boolean
+            | InferredSchema::Array { .. }
+            | InferredSchema::Properties { .. }
+            | InferredSchema::Values(_)
+            | InferredSchema::Discriminator { .. }
+            | InferredSchema::TaggedElements { .. } = self
+            {
+                return Err(InferError::EnumHintMismatch {
+                    path: vec![],
+                    inferred: self.describe(),
+                });
+            }
+        }
+
+        Ok(match self {
+            // A position with no data to go on but nulls, or with no data at
+            // all, like the elements of arrays that were always empty.
+            InferredSchema::Unknown => match hints.empty_fallback() {
+                EmptyFallback::Empty => Schema::Empty {
+                    definitions: Default::default(),
+                    metadata: Default::default(),
+                },
+                EmptyFallback::Unknown => {
+                    let mut metadata = BTreeMap::new();
+                    metadata.insert("unknown".to_owned(), Value::Bool(true));
+
+                    Schema::Empty {
+                        definitions: Default::default(),
+                        metadata,
+                    }
+                }
+                EmptyFallback::Number => Schema::Type {
+                    definitions: Default::default(),
+                    metadata: Default::default(),
+                    nullable: false,
+                    type_: hints.num_type().into_type(),
+                },
             },
-            InferredSchema::Boolean => Schema::Type {
+            InferredSchema::Any { .. } => Schema::Empty {
                 definitions: Default::default(),
                 metadata: Default::default(),
-                nullable: false,
-                type_: Type::Boolean,
             },
-            InferredSchema::Number(inferred_number) => Schema::Type {
+            InferredSchema::Boolean => Schema::Type {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                type_: inferred_number.into_type(hints.default_num_type()),
+                type_: Type::Boolean,
             },
+            InferredSchema::Number(inferred_number)
+                if inferred_number.big() && hints.big_integers() == BigIntegers::String =>
+            {
+                let mut metadata = BTreeMap::new();
+                metadata.insert("bigint".to_owned(), Value::Bool(true));
+
+                Schema::Type {
+                    definitions: Default::default(),
+                    metadata,
+                    nullable: false,
+                    type_: Type::String,
+                }
+            }
+            InferredSchema::Number(inferred_number) => {
+                let mut metadata = BTreeMap::new();
+                if let Some(histogram) = inferred_number.histogram() {
+                    metadata.insert("histogram".to_owned(), histogram);
+                }
+
+                Schema::Type {
+                    definitions: Default::default(),
+                    metadata,
+                    nullable: false,
+                    type_: inferred_number.to_type(hints.num_type()),
+                }
+            }
             InferredSchema::String => Schema::Type {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
                 type_: Type::String,
             },
-            InferredSchema::Timestamp => Schema::Type {
+            InferredSchema::SampledString {
+                timestamps: true, ..
+            } => Schema::Type {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
                 type_: Type::Timestamp,
             },
-            InferredSchema::Enum(values) => Schema::Enum {
+            InferredSchema::SampledString { samples, .. } => Schema::Type {
                 definitions: Default::default(),
-                metadata: Default::default(),
+                metadata: hints.format_registry().detect(&samples.samples()),
                 nullable: false,
-                enum_: values,
+                type_: Type::String,
             },
-            InferredSchema::Array(sub_infer) => Schema::Elements {
+            InferredSchema::Enum(values) => Schema::Enum {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                elements: Box::new(sub_infer.into_schema(hints)),
+                enum_: values,
             },
-            InferredSchema::Properties { required, optional } => {
-                let properties_is_present = !required.is_empty();
+            // Without an enum hint, integers are only collected as a guess, and
+            // the guess only holds if some of them repeated.
+            InferredSchema::NumericEnum(values)
+                if !hints.is_enum_active() && values.values().all(|&count| count == 1) =>
+            {
+                InferredSchema::Number(numbers_of(&values, new_number(hints))).into_schema(hints)?
+            }
+            InferredSchema::NumericEnum(values) => {
+                let mut metadata = BTreeMap::new();
+                metadata.insert("numericEnum".to_owned(), Value::Bool(true));
 
-                Schema::Properties {
+                Schema::Enum {
                     definitions: Default::default(),
-                    metadata: Default::default(),
+                    metadata,
                     nullable: false,
-                    properties: required
-                        .into_iter()
-                        .map(|(k, v)| (k, v.into_schema(hints)))
-                        .collect(),
-                    optional_properties: optional
+                    enum_: values.into_keys().map(|v| v.to_string()).collect(),
+                }
+            }
+            InferredSchema::Array { elements, tuple } => {
+                // The schema of each index is only informational, since JSON
+                // Typedef has no way to check arrays index by index. The
+                // elements are still checked against `elements`, which accepts
+                // every index.
+                let mut metadata = BTreeMap::new();
+                if let Some(tuple) = tuple.filter(|tuple| !tuple.is_empty()) {
+                    let tuple = tuple
                         .into_iter()
-                        .map(|(k, v)| (k, v.into_schema(hints)))
-                        .collect(),
-                    properties_is_present,
-                    additional_properties: false,
+                        .enumerate()
+                        .map(|(i, sub_infer)| {
+                            let key = i.to_string();
+                            let schema = sub_infer
+                                .into_schema(&hints.sub_hints(&key))
+                                .map_err(|err| err.within(&key))?;
+                            Ok(serde_json::to_value(schema.into_serde_schema()).unwrap())
+                        })
+                        .collect::<Result<_, InferError>>()?;
+                    metadata.insert("tuple".to_owned(), Value::Array(tuple));
+                }
+
+                Schema::Elements {
+                    definitions: Default::default(),
+                    metadata,
+                    nullable: false,
+                    elements: Box::new(
+                        elements
+                            .into_schema(&hints.sub_hints(WILDCARD))
+                            .map_err(|err| err.within(WILDCARD))?,
+                    ),
+                }
+            }
+            InferredSchema::Properties {
+                required,
+                optional,
+                objects,
+            } => {
+                let schema = properties_schema(required, optional, objects, hints)?;
+                let mut schema = match hints.values_collapse() {
+                    Some(min_optional) => collapse_into_values(schema, min_optional),
+                    None => schema,
+                };
+
+                // Properties are only described once they can no longer be
+                // collapsed, since their descriptions would all differ.
+                if hints.key_descriptions() {
+                    describe_properties(&mut schema);
                 }
+
+                schema
             }
             InferredSchema::Values(sub_infer) => Schema::Values {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                values: Box::new(sub_infer.into_schema(hints)),
+                values: Box::new(
+                    sub_infer
+                        .into_schema(&hints.sub_hints(WILDCARD))
+                        .map_err(|err| err.within(WILDCARD))?,
+                ),
             },
+            InferredSchema::Discriminator {
+                discriminator,
+                untagged,
+                ..
+            } if untagged > 0 => {
+                return Err(InferError::DiscriminatorTagMissing {
+                    path: vec![],
+                    discriminator,
+                    untagged,
+                })
+            }
             InferredSchema::Discriminator {
                 discriminator,
                 mapping,
+                ..
             } => Schema::Discriminator {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
                 discriminator,
+                // The variants of a discriminator have to stay in the
+                // properties form, and so are never collapsed into values.
                 mapping: mapping
                     .into_iter()
-                    .map(|(k, v)| (k, v.into_schema(hints)))
-                    .collect(),
+                    .map(|(k, v)| {
+                        let sub_schema = match v {
+                            InferredSchema::Properties {
+                                required,
+                                optional,
+                                objects,
+                            } => {
+                                let mut schema =
+                                    properties_schema(required, optional, objects, hints)?;
+                                if hints.key_descriptions() {
+                                    describe_properties(&mut schema);
+                                }
+                                schema
+                            }
+                            v => v.into_schema(hints)?,
+                        };
+                        Ok((k, sub_schema))
+                    })
+                    .collect::<Result<_, _>>()?,
             },
-            InferredSchema::Nullable(sub_infer) => match sub_infer.into_schema(hints) {
+            InferredSchema::TaggedElements { merged, mut tagged } => {
+                match element_tag(&tagged).map(str::to_owned) {
+                    Some(discriminator) => InferredSchema::Discriminator {
+                        mapping: tagged.remove(&discriminator).unwrap(),
+                        discriminator,
+                        untagged: 0,
+                    }
+                    .into_schema(hints)?,
+                    None => merged.into_schema(hints)?,
+                }
+            }
+            // When `nullable` can't be emitted, nulls are either accepted by
+            // accepting anything, or left out of the schema altogether.
+            InferredSchema::Nullable(_)
+                if hints.nullable_fallback() == Some(NullableFallback::Empty) =>
+            {
+                Schema::Empty {
+                    definitions: Default::default(),
+                    metadata: Default::default(),
+                }
+            }
+            InferredSchema::Nullable(sub_infer)
+                if hints.nullable_fallback() == Some(NullableFallback::Base) =>
+            {
+                sub_infer.into_schema(hints)?
+            }
+            InferredSchema::Nullable(sub_infer) => match sub_infer.into_schema(hints)? {
                 Schema::Ref { .. } => unreachable!("ref form inferred"),
 
+                // The empty form already accepts null, and so there's nothing
+                // to add to it. This is what a position that has only ever been
+                // null becomes: it's permissive, and any non-null data seen
+                // later will refine it into a nullable schema of that data's
+                // type.
                 s @ Schema::Empty { .. } => s,
                 Schema::Type {
                     definitions,
@@ -394,6 +805,2736 @@ impl InferredSchema {
                     mapping,
                 },
             },
+        })
+    }
+
+    /// Converts the inference into a schema of the given type, as requested by
+    /// a type hint.
+    ///
+    /// Nulls are treated the same way as for an inferred type: see
+    /// [`Hints::with_emit_nullable`].
+    fn into_type_schema(self, type_: Type, hints: &Hints) -> Result<Schema, InferError> {
+        let (sub_infer, nullable) = match self {
+            InferredSchema::Nullable(sub_infer) => (*sub_infer, true),
+            sub_infer => (sub_infer, false),
+        };
+
+        if !sub_infer.accepted_by(&type_) {
+            return Err(InferError::TypeHintMismatch {
+                path: vec![],
+                type_,
+                inferred: sub_infer.describe(),
+            });
+        }
+
+        Ok(match (nullable, hints.nullable_fallback()) {
+            (true, Some(NullableFallback::Empty)) => Schema::Empty {
+                definitions: Default::default(),
+                metadata: Default::default(),
+            },
+            (true, Some(NullableFallback::Base)) => Schema::Type {
+                definitions: Default::default(),
+                metadata: Default::default(),
+                nullable: false,
+                type_,
+            },
+            _ => Schema::Type {
+                definitions: Default::default(),
+                metadata: Default::default(),
+                nullable,
+                type_,
+            },
+        })
+    }
+
+    /// Whether a schema of the given type would accept all of the examples
+    /// that went into the inference.
+    ///
+    /// Nullable inferences must be unwrapped before calling this.
+    fn accepted_by(&self, type_: &Type) -> bool {
+        match (self, type_) {
+            (InferredSchema::Unknown, _) => true,
+            (InferredSchema::Boolean, Type::Boolean) => true,
+            (InferredSchema::String, Type::String) => true,
+            (InferredSchema::SampledString { .. }, Type::String) => true,
+            (InferredSchema::SampledString { timestamps, .. }, Type::Timestamp) => *timestamps,
+            (InferredSchema::Enum(_), Type::String) => true,
+            (InferredSchema::Number(inferred_number), _) => {
+                NumType::from_type(type_).is_some_and(|t| inferred_number.contained_by(&t))
+            }
+            (InferredSchema::NumericEnum(values), _) => {
+                let inferred_number = numbers_of(values, InferredNumber::new());
+
+                NumType::from_type(type_).is_some_and(|t| inferred_number.contained_by(&t))
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects warnings about the inference, and everything within it, into
+    /// `warnings`. `path` is the path to the inference, and is left as it was
+    /// found.
+    ///
+    /// Hints are followed down the same paths as in
+    /// [`InferredSchema::into_schema`], so that numbers are checked against the
+    /// number type that would be tried first for them.
+    pub fn warnings(&self, hints: &Hints, path: &mut Vec<String>, warnings: &mut Vec<Warning>) {
+        self.visit(hints, path, &mut |inference, hints, path| match inference {
+            InferredSchema::Any { kinds } => warnings.push(Warning::TypeConflict {
+                path: path.to_vec(),
+                kinds: kinds.iter().copied().collect(),
+            }),
+            InferredSchema::Number(inferred_number)
+                if inferred_number.big() && hints.big_integers() == BigIntegers::String => {}
+            // A type hint decides the type regardless of the numbers seen.
+            InferredSchema::Number(inferred_number) if hints.active_type().is_none() => {
+                if inferred_number.big() {
+                    warnings.push(Warning::BigInteger {
+                        path: path.to_vec(),
+                    });
+                }
+
+                let default = hints.num_type();
+                if inferred_number.non_finite() {
+                    warnings.push(Warning::NonFiniteNumber {
+                        path: path.to_vec(),
+                    });
+                } else if let Some((min, max)) = inferred_number.exceeds(default) {
+                    warnings.push(Warning::NumberOutOfRange {
+                        path: path.to_vec(),
+                        default: *default,
+                        min,
+                        max,
+                        inferred: inferred_number.to_type(default),
+                    });
+                }
+            }
+            _ => {}
+        });
+    }
+
+    /// Collects the paths to the parts of the inference that were to be
+    /// inferred as an enum, but that fell back to a plain string or number,
+    /// into `fallbacks`. Paths are followed as in [`InferredSchema::warnings`].
+    pub fn enum_fallbacks(
+        &self,
+        hints: &Hints,
+        path: &mut Vec<String>,
+        fallbacks: &mut Vec<Vec<String>>,
+    ) {
+        self.visit(hints, path, &mut |inference, hints, path| {
+            let fell_back = match inference {
+                // Strings only start out as anything else without an enum hint.
+                InferredSchema::String => hints.is_enum_active(),
+                InferredSchema::Number(_) => hints.numeric_enum_size().is_some(),
+                InferredSchema::NumericEnum(values) => {
+                    !hints.is_enum_active() && values.values().all(|&count| count == 1)
+                }
+                _ => false,
+            };
+
+            if fell_back && hints.active_type().is_none() {
+                fallbacks.push(path.to_vec());
+            }
+        });
+    }
+
+    /// What was inferred at `path` within the inference, if anything was.
+    ///
+    /// Paths are followed as in [`InferredSchema::warnings`], except that any
+    /// key leads to the values of a [`InferredSchema::Values`]. Within a
+    /// discriminator, the other properties are looked for in every mapping.
+    /// If the mappings disagree about them, they're [`InferredKind::Any`].
+    pub fn kind_at(&self, hints: &Hints, path: &[String]) -> Option<InferredKind> {
+        let (key, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Some(self.kind(hints)),
+        };
+
+        // Nothing is inferred within a part of the input that's hinted to be
+        // of some type, or to accept anything.
+        if hints.is_any_active() || hints.active_type().is_some() {
+            return None;
+        }
+
+        match self {
+            InferredSchema::Array { elements, .. } if key == WILDCARD => {
+                elements.kind_at(&hints.sub_hints(WILDCARD), rest)
+            }
+            InferredSchema::Values(sub_infer) => {
+                sub_infer.kind_at(&hints.sub_hints(WILDCARD), rest)
+            }
+            InferredSchema::Properties {
+                required, optional, ..
+            } => required
+                .get(key)
+                .or_else(|| optional.get(key))?
+                .kind_at(&hints.sub_hints(key), rest),
+            InferredSchema::Discriminator {
+                discriminator,
+                mapping,
+                ..
+            } => mapping_kind_at(discriminator, mapping, hints, path),
+            InferredSchema::TaggedElements { merged, tagged } => match element_tag(tagged) {
+                Some(tag) => mapping_kind_at(tag, &tagged[tag], hints, path),
+                None => merged.kind_at(hints, path),
+            },
+            InferredSchema::Nullable(sub_infer) => sub_infer.kind_at(hints, path),
+            _ => None,
+        }
+    }
+
+    /// What the inference is, as described by [`InferredKind`].
+    fn kind(&self, hints: &Hints) -> InferredKind {
+        if hints.is_any_active() {
+            return InferredKind::Any;
+        }
+
+        if let Some(type_) = hints.active_type() {
+            let kind = match type_ {
+                Type::Boolean => InferredKind::Boolean,
+                Type::String => InferredKind::String,
+                Type::Timestamp => InferredKind::Timestamp,
+                _ => InferredKind::Number,
+            };
+
+            return match self {
+                InferredSchema::Nullable(_) => InferredKind::Nullable(Box::new(kind)),
+                _ => kind,
+            };
+        }
+
+        match self {
+            InferredSchema::Unknown => InferredKind::Unknown,
+            InferredSchema::Any { .. } => InferredKind::Any,
+            InferredSchema::Boolean => InferredKind::Boolean,
+            InferredSchema::Number(inferred_number)
+                if inferred_number.big() && hints.big_integers() == BigIntegers::String =>
+            {
+                InferredKind::String
+            }
+            InferredSchema::Number(_) => InferredKind::Number,
+            InferredSchema::String => InferredKind::String,
+            InferredSchema::SampledString {
+                timestamps: true, ..
+            } => InferredKind::Timestamp,
+            InferredSchema::SampledString { .. } => InferredKind::String,
+            InferredSchema::Enum(_) => InferredKind::Enum,
+
+            // The same fallback as in enum_fallbacks.
+            InferredSchema::NumericEnum(values)
+                if !hints.is_enum_active() && values.values().all(|&count| count == 1) =>
+            {
+                InferredKind::Number
+            }
+            InferredSchema::NumericEnum(_) => InferredKind::Enum,
+            InferredSchema::Array { .. } => InferredKind::Array,
+            InferredSchema::Properties { .. } => InferredKind::Object,
+            InferredSchema::Values(_) => InferredKind::Values,
+            InferredSchema::Discriminator { .. } => InferredKind::Discriminator,
+            InferredSchema::TaggedElements { merged, tagged } => match element_tag(tagged) {
+                Some(_) => InferredKind::Discriminator,
+                None => merged.kind(hints),
+            },
+            InferredSchema::Nullable(sub_infer) => {
+                InferredKind::Nullable(Box::new(sub_infer.kind(hints)))
+            }
+        }
+    }
+
+    /// Calls `f` with the inference and everything within it, along with the
+    /// hints and the path for each.
+    ///
+    /// This follows the same paths as [`InferredSchema::into_schema`]. In
+    /// particular, only the variants of the tag that would be chosen are
+    /// visited in [`InferredSchema::TaggedElements`].
+    fn visit(
+        &self,
+        hints: &Hints,
+        path: &mut Vec<String>,
+        f: &mut dyn FnMut(&InferredSchema, &Hints, &[String]),
+    ) {
+        f(self, hints, path);
+
+        match self {
+            InferredSchema::Array {
+                elements: sub_infer,
+                ..
+            }
+            | InferredSchema::Values(sub_infer) => {
+                path.push(WILDCARD.to_owned());
+                sub_infer.visit(&hints.sub_hints(WILDCARD), path, f);
+                path.pop();
+            }
+            InferredSchema::Properties {
+                required, optional, ..
+            } => {
+                for (k, sub_infer) in required.iter().chain(optional) {
+                    path.push(k.clone());
+                    sub_infer.visit(&hints.sub_hints(k), path, f);
+                    path.pop();
+                }
+            }
+            InferredSchema::Discriminator { mapping, .. } => {
+                for sub_infer in mapping.values() {
+                    sub_infer.visit(hints, path, f);
+                }
+            }
+            InferredSchema::TaggedElements { merged, tagged } => match element_tag(tagged) {
+                Some(tag) => {
+                    for sub_infer in tagged[tag].values() {
+                        sub_infer.visit(hints, path, f);
+                    }
+                }
+                None => merged.visit(hints, path, f),
+            },
+            InferredSchema::Nullable(sub_infer) => sub_infer.visit(hints, path, f),
+            _ => {}
+        }
+    }
+
+    /// The inference for a position that has seen both what `prior` was
+    /// inferred from and `value`, which no one schema but the empty form
+    /// accepts.
+    fn conflict(prior: &InferredSchema, value: &Value) -> InferredSchema {
+        InferredSchema::Any {
+            kinds: vec![prior.describe(), describe_value(value)]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// A short description of the inference, for use in error messages.
+    fn describe(&self) -> &'static str {
+        match self {
+            InferredSchema::Unknown => "nothing",
+            InferredSchema::Any { .. } => "a mix of types",
+            InferredSchema::Boolean => "booleans",
+            InferredSchema::Number(_) | InferredSchema::NumericEnum(_) => "numbers",
+            InferredSchema::String
+            | InferredSchema::SampledString { .. }
+            | InferredSchema::Enum(_) => "strings",
+            InferredSchema::Array { .. } => "arrays",
+            InferredSchema::Properties { .. }
+            | InferredSchema::Values(_)
+            | InferredSchema::Discriminator { .. }
+            | InferredSchema::TaggedElements { .. } => "objects",
+            InferredSchema::Nullable(sub_infer) => sub_infer.describe(),
         }
     }
 }
+
+/// The entries of an object, keyed by the spelling of each key that inference
+/// should use.
+///
+/// Keys are used as-is, unless the hints say keys are case-insensitive. In that
+/// case, each key is replaced by the first spelling of it that was seen: one of
+/// the `known` keys if possible, or else the first one in `map`. The keys of a
+/// `map` are in sorted order, and so that's the order used to choose between
+/// spellings within a single object.
+fn canonical_entries<'k>(
+    map: Map<String, Value>,
+    known: impl Iterator<Item = &'k String>,
+    hints: &Hints,
+) -> Vec<(String, Value)> {
+    if !hints.case_insensitive_keys() {
+        return map.into_iter().collect();
+    }
+
+    let mut spellings: HashMap<String, String> =
+        known.map(|k| (k.to_lowercase(), k.clone())).collect();
+
+    map.into_iter()
+        .map(|(k, v)| {
+            let k = spellings.entry(k.to_lowercase()).or_insert(k).clone();
+            (k, v)
+        })
+        .collect()
+}
+
+/// What a scalar inference becomes when it meets `value`, which is of some other
+/// kind.
+///
+/// JSON Typedef has no union of scalar types, and so by default the position
+/// becomes the empty form. If the hints say to, a mix of scalars is instead
+/// inferred as a string. Anything mixed with an array or object is always the
+/// empty form.
+fn mixed_scalars(prior: &InferredSchema, value: &Value, hints: &Hints) -> InferredSchema {
+    let is_scalar = matches!(value, Value::Bool(_) | Value::Number(_) | Value::String(_));
+
+    match hints.mixed_scalars() {
+        MixedScalars::String if is_scalar => InferredSchema::String,
+        _ => InferredSchema::conflict(prior, value),
+    }
+}
+
+/// A short description of a kind of value, in the same terms as
+/// [`InferredSchema::describe`].
+fn describe_value(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "nulls",
+        Value::Bool(_) => "booleans",
+        Value::Number(_) => "numbers",
+        Value::String(_) => "strings",
+        Value::Array(_) => "arrays",
+        Value::Object(_) => "objects",
+    }
+}
+
+/// Infers the first object seen at a position in the properties form, with
+/// every property required.
+fn infer_properties(obj: Map<String, Value>, hints: &Hints) -> InferredSchema {
+    // If keys are case-insensitive, one object may have more than one spelling
+    // of the same key. Their values are merged.
+    let mut props = BTreeMap::new();
+    for (k, v) in canonical_entries(obj, std::iter::empty(), hints) {
+        let sub_infer = props
+            .remove(&k)
+            .unwrap_or(InferredSchema::Unknown)
+            .infer(v, &hints.sub_hints(&k));
+        props.insert(k, sub_infer);
+    }
+
+    InferredSchema::Properties {
+        required: props,
+        optional: BTreeMap::new(),
+        objects: 1,
+    }
+}
+
+/// Infers `value`, an element of an array, into `prior`, the inference for the
+/// elements before it.
+///
+/// Only the elements of arrays are checked for a tag, and so this is where
+/// objects start being inferred as possibly sharing one.
+fn infer_element(prior: InferredSchema, value: Value, hints: &Hints) -> InferredSchema {
+    match (prior, value) {
+        (InferredSchema::Unknown, Value::Object(obj)) if hints.max_element_tags().is_some() => {
+            let tagged = obj
+                .iter()
+                .filter(|(_, v)| v.is_string())
+                .map(|(k, _)| (k.clone(), BTreeMap::new()))
+                .collect();
+
+            InferredSchema::TaggedElements {
+                merged: Box::new(InferredSchema::Unknown),
+                tagged,
+            }
+            .infer(Value::Object(obj), hints)
+        }
+        (InferredSchema::Nullable(prior), value @ Value::Object(_)) => {
+            InferredSchema::Nullable(Box::new(infer_element(*prior, value, hints)))
+        }
+        (prior, value) => prior.infer(value, hints),
+    }
+}
+
+/// Converts the properties of an inferred object, seen `objects` times, into a
+/// schema of the properties form.
+fn properties_schema(
+    mut required: BTreeMap<String, InferredSchema>,
+    mut optional: BTreeMap<String, InferredSchema>,
+    objects: usize,
+    hints: &Hints,
+) -> Result<Schema, InferError> {
+    // With too few objects, a property that was always there may just not
+    // have been left out yet.
+    if !hints.allows_required(objects) {
+        optional.append(&mut required);
+    }
+
+    if hints.absent_as_nullable() {
+        for (k, v) in std::mem::take(&mut optional) {
+            let v = match v {
+                v @ InferredSchema::Nullable(_) => v,
+                v => InferredSchema::Nullable(Box::new(v)),
+            };
+
+            required.insert(k, v);
+        }
+    }
+
+    let properties_is_present = !required.is_empty();
+
+    Ok(Schema::Properties {
+        definitions: Default::default(),
+        metadata: Default::default(),
+        nullable: false,
+        properties: required
+            .into_iter()
+            .map(|(k, v)| {
+                let sub_schema = v
+                    .into_schema(&hints.sub_hints(&k))
+                    .map_err(|err| err.within(&k))?;
+                Ok((k, sub_schema))
+            })
+            .collect::<Result<_, _>>()?,
+        optional_properties: optional
+            .into_iter()
+            .map(|(k, v)| {
+                let sub_schema = v
+                    .into_schema(&hints.sub_hints(&k))
+                    .map_err(|err| err.within(&k))?;
+                Ok((k, sub_schema))
+            })
+            .collect::<Result<_, _>>()?,
+        properties_is_present,
+        additional_properties: hints.is_open_object_active(),
+    })
+}
+
+/// Rewrites a schema of the properties form as the values form, if it has at
+/// least `min_optional` optional properties, and every property, required or
+/// optional, has the same schema.
+///
+/// The values form accepts every object the properties form did, since each
+/// of their values has that one schema. It also accepts objects that leave out
+/// required properties, or that have other keys, which is the point: keys
+/// this numerous are taken to be data rather than names.
+fn collapse_into_values(schema: Schema, min_optional: usize) -> Schema {
+    match schema {
+        Schema::Properties {
+            definitions,
+            metadata,
+            nullable,
+            properties,
+            optional_properties,
+            ..
+        } if optional_properties.len() >= min_optional.max(1)
+            && properties
+                .values()
+                .chain(optional_properties.values())
+                .all(|sub_schema| Some(sub_schema) == optional_properties.values().next()) =>
+        {
+            Schema::Values {
+                definitions,
+                metadata,
+                nullable,
+                values: Box::new(optional_properties.into_values().next().unwrap()),
+            }
+        }
+        schema => schema,
+    }
+}
+
+/// What was inferred at `path` within the objects of a discriminator, with
+/// `tag` as the discriminator and the given mapping. The tag itself is always
+/// a string. See [`InferredSchema::kind_at`].
+fn mapping_kind_at(
+    tag: &str,
+    mapping: &BTreeMap<String, InferredSchema>,
+    hints: &Hints,
+    path: &[String],
+) -> Option<InferredKind> {
+    if path == [tag] {
+        return Some(InferredKind::String);
+    }
+
+    let mut kinds = mapping
+        .values()
+        .filter_map(|sub_infer| sub_infer.kind_at(hints, path));
+    let first = kinds.next()?;
+
+    Some(if kinds.all(|kind| kind == first) {
+        first
+    } else {
+        InferredKind::Any
+    })
+}
+
+/// The tag that objects inferred as [`InferredSchema::TaggedElements`] share,
+/// if any. A tag has to have taken more than one value, and the one with the
+/// fewest values wins, followed by the first in key order.
+fn element_tag(tagged: &BTreeMap<String, BTreeMap<String, InferredSchema>>) -> Option<&str> {
+    tagged
+        .iter()
+        .filter(|(_, mapping)| mapping.len() > 1)
+        .min_by_key(|(_, mapping)| mapping.len())
+        .map(|(tag, _)| tag.as_str())
+}
+
+fn new_number(hints: &Hints) -> InferredNumber {
+    if hints.histograms() {
+        InferredNumber::new().with_histogram()
+    } else {
+        InferredNumber::new()
+    }
+}
+
+/// Folds the integers collected for a numeric enum, as many times as each was
+/// seen, into `inferred_number`.
+fn numbers_of(values: &BTreeMap<i64, usize>, inferred_number: InferredNumber) -> InferredNumber {
+    values
+        .iter()
+        .flat_map(|(&value, &count)| std::iter::repeat(value).take(count))
+        .fold(inferred_number, |n, value| n.infer(value as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::StringFormatDetector;
+    use crate::hints::{BooleanStrings, DateDetection, HintMap, HintSet, MixedScalars};
+    use crate::inferred_number::NumType;
+    use serde_json::json;
+
+    fn infer_all(hints: &Hints, values: Vec<Value>) -> Value {
+        let mut inference = InferredSchema::Unknown;
+        for value in values {
+            inference = inference.infer(value, hints);
+        }
+
+        serde_json::to_value(inference.into_schema(hints).unwrap().into_serde_schema()).unwrap()
+    }
+
+    #[test]
+    fn values_with_enum_values() {
+        let values_path = vec!["config".to_string()];
+        let enum_path = vec!["config".to_string(), "-".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "config": { "values": { "enum": ["x", "y", "z"] } },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "config": { "a": "x", "b": "y" } }),
+                    json!({ "config": { "c": "z" } }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn null_only() {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "properties": { "x": {} } }),
+            infer_all(&hints, vec![json!({ "x": null }), json!({ "x": null })])
+        );
+
+        assert_eq!(
+            json!({ "properties": { "x": { "type": "uint8", "nullable": true } } }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "x": null }),
+                    json!({ "x": null }),
+                    json!({ "x": 5 }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn nullable_elements() {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "elements": { "type": "string", "nullable": true } }),
+            infer_all(&hints, vec![json!(["a", null, "b"])])
+        );
+
+        assert_eq!(
+            json!({ "elements": { "type": "string", "nullable": true } }),
+            infer_all(&hints, vec![json!(["a"]), json!([null, null])])
+        );
+
+        // An all-null array has nothing to go on but null, and so its elements
+        // are the empty form, which accepts null.
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([null, null, null])])
+        );
+    }
+
+    #[test]
+    fn nullable_arrays() {
+        let hints = Hints::default();
+
+        // A property that's sometimes an array and sometimes null is a nullable
+        // array, whichever order the two come in.
+        assert_eq!(
+            json!({
+                "properties": { "xs": { "elements": { "type": "uint8" }, "nullable": true } },
+            }),
+            infer_all(&hints, vec![json!({ "xs": [1, 2] }), json!({ "xs": null })])
+        );
+
+        assert_eq!(
+            json!({
+                "properties": { "xs": { "elements": { "type": "uint8" }, "nullable": true } },
+            }),
+            infer_all(&hints, vec![json!({ "xs": null }), json!({ "xs": [1, 2] })])
+        );
+
+        // Whereas nulls inside of the array make for nullable elements.
+        assert_eq!(
+            json!({
+                "properties": { "xs": { "elements": { "type": "uint8", "nullable": true } } },
+            }),
+            infer_all(&hints, vec![json!({ "xs": [1, null] })])
+        );
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "xs": { "elements": { "type": "uint8", "nullable": true }, "nullable": true },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({ "xs": [1, null] }), json!({ "xs": null })]
+            )
+        );
+    }
+
+    #[test]
+    fn array_element_shapes() {
+        let hints = Hints::default();
+
+        // Every element of every array goes into one element schema, not just
+        // the first, so keys that some elements lack are optional.
+        assert_eq!(
+            json!({
+                "elements": {
+                    "optionalProperties": {
+                        "a": { "type": "uint8" },
+                        "b": { "type": "uint8" },
+                    },
+                },
+            }),
+            infer_all(&hints, vec![json!([{ "a": 1 }, { "b": 2 }])])
+        );
+
+        // That holds across examples too, and nulls among the elements make
+        // the element schema nullable.
+        assert_eq!(
+            json!({
+                "elements": {
+                    "properties": { "a": { "type": "uint8", "nullable": true } },
+                    "optionalProperties": { "b": { "type": "uint8" } },
+                    "nullable": true,
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!([{ "a": 1 }]),
+                    json!([{ "a": null, "b": 2 }, null]),
+                    json!([]),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn array_nullability() {
+        let hints = Hints::default();
+
+        // Neither the array nor its elements are nullable. Empty arrays don't
+        // contribute anything to the elements.
+        assert_eq!(
+            json!({ "properties": { "xs": { "elements": { "type": "string" } } } }),
+            infer_all(&hints, vec![json!({ "xs": ["a"] }), json!({ "xs": [] })])
+        );
+
+        // Only the array is nullable.
+        assert_eq!(
+            json!({
+                "properties": { "xs": { "elements": { "type": "string" }, "nullable": true } },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "xs": ["a"] }),
+                    json!({ "xs": [] }),
+                    json!({ "xs": null })
+                ]
+            )
+        );
+
+        // Only the elements are nullable, even if the null was the only
+        // element of its array.
+        assert_eq!(
+            json!({
+                "properties": { "xs": { "elements": { "type": "string", "nullable": true } } },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "xs": ["a"] }),
+                    json!({ "xs": [null] }),
+                    json!({ "xs": [] })
+                ]
+            )
+        );
+
+        // Both are nullable.
+        assert_eq!(
+            json!({
+                "properties": {
+                    "xs": { "elements": { "type": "string", "nullable": true }, "nullable": true },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "xs": [null] }),
+                    json!({ "xs": null }),
+                    json!({ "xs": ["a"] })
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn emit_nullable() {
+        let type_path = vec!["b".to_owned()];
+        let examples = vec![
+            json!({ "a": "x", "b": 1, "c": [null] }),
+            json!({ "a": null, "b": null, "c": null }),
+        ];
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": {},
+                    "b": {},
+                    "c": {},
+                },
+            }),
+            infer_all(
+                &Hints::default()
+                    .with_emit_nullable(false)
+                    .with_type_hints(HintMap::new(vec![(&type_path, Type::Uint32)])),
+                examples.clone()
+            )
+        );
+
+        // Nulls inside of the array are left out, too.
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "type": "string" },
+                    "b": { "type": "uint32" },
+                    "c": { "elements": {} },
+                },
+            }),
+            infer_all(
+                &Hints::default()
+                    .with_emit_nullable(false)
+                    .with_nullable_fallback(NullableFallback::Base)
+                    .with_type_hints(HintMap::new(vec![(&type_path, Type::Uint32)])),
+                examples.clone()
+            )
+        );
+
+        // The fallback doesn't matter unless nullable is turned off.
+        assert_eq!(
+            infer_all(&Hints::default(), examples.clone()),
+            infer_all(
+                &Hints::default().with_nullable_fallback(NullableFallback::Base),
+                examples
+            )
+        );
+    }
+
+    #[test]
+    fn nested_values() {
+        let values_path = vec![];
+        let examples = vec![json!({
+            "a": { "x": 1 },
+            "b": { "y": 2, "z": 300 },
+            "c": { "x": 3, "z": null },
+        })];
+
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({
+                "values": {
+                    "optionalProperties": {
+                        "x": { "type": "uint8" },
+                        "y": { "type": "uint8" },
+                        "z": { "type": "uint16", "nullable": true },
+                    },
+                },
+            }),
+            infer_all(&hints, examples.clone())
+        );
+
+        // The inner maps share one schema for their values, merged across the
+        // three outer keys.
+        assert_eq!(
+            json!({ "values": { "values": { "type": "uint16", "nullable": true } } }),
+            infer_all(&hints.with_nested_values(true), examples.clone())
+        );
+
+        // Which is the same as giving each level its own values hint.
+        let inner_values_path = vec!["-".to_owned()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![&values_path, &inner_values_path]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "values": { "values": { "type": "uint16", "nullable": true } } }),
+            infer_all(&hints, examples)
+        );
+    }
+
+    #[test]
+    fn nullable_values() {
+        let values_path = vec![];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "values": { "type": "uint8", "nullable": true } }),
+            infer_all(
+                &hints,
+                vec![json!({ "a": 1, "b": null }), json!({ "c": 2 })]
+            )
+        );
+
+        // The values being nullable is independent from the map itself being
+        // nullable.
+        assert_eq!(
+            json!({ "values": { "type": "uint8" }, "nullable": true }),
+            infer_all(&hints, vec![json!({ "a": 1 }), json!(null)])
+        );
+    }
+
+    #[test]
+    fn numeric_enum() {
+        let enum_path = vec![];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "enum": ["-2", "-30", "1", "3"], "metadata": { "numericEnum": true } }),
+            infer_all(
+                &hints,
+                vec![json!(1), json!(-2), json!(3), json!(-30), json!(1)]
+            )
+        );
+
+        assert_eq!(
+            json!({
+                "enum": ["1", "2"],
+                "metadata": { "numericEnum": true },
+                "nullable": true,
+            }),
+            infer_all(&hints, vec![json!(1), json!(null), json!(2)])
+        );
+
+        // Zero is the same integer however it's written.
+        assert_eq!(
+            json!({ "enum": ["0", "1"], "metadata": { "numericEnum": true } }),
+            infer_all(&hints, vec![json!(-0.0), json!(0), json!(1.0), json!(0.0)])
+        );
+
+        // Non-integers can't be represented, and so fall back to a number.
+        assert_eq!(
+            json!({ "type": "float64" }),
+            infer_all(&hints, vec![json!(1), json!(-2), json!(1.5)])
+        );
+
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_max_enum_size(2);
+
+        assert_eq!(
+            json!({ "type": "int16" }),
+            infer_all(&hints, vec![json!(1), json!(-200), json!(3)])
+        );
+    }
+
+    #[test]
+    fn open_objects() {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_open_objects(true);
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": {
+                        "properties": { "b": { "type": "boolean" } },
+                        "additionalProperties": true,
+                    },
+                },
+                "additionalProperties": true,
+            }),
+            infer_all(&hints, vec![json!({ "a": { "b": true } })])
+        );
+    }
+
+    #[test]
+    fn any_paths() {
+        let any_path = vec!["extra".to_owned()];
+        let code_path = vec!["extra".to_owned(), "code".to_owned()];
+        let hints = Hints::default()
+            .with_any_paths(HintSet::new(vec![&any_path]))
+            .with_unknown_as_number(true)
+            .with_type_hints(HintMap::new(vec![(&code_path, Type::Boolean)]));
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "extra": {},
+                    "id": { "type": "uint8" },
+                    "tags": { "elements": { "type": "string" } },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({
+                        "id": 1,
+                        "tags": ["a"],
+                        "extra": { "code": 3, "nested": [{ "a": [1, "x"] }] },
+                    }),
+                    json!({ "id": 2, "tags": [], "extra": null }),
+                    json!({ "id": 3, "tags": ["b"], "extra": "anything" }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn open_object_paths() {
+        let open_path = vec!["a".to_string(), "-".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_open_object_paths(HintSet::new(vec![&open_path]));
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": {
+                        "elements": {
+                            "properties": {
+                                "b": { "properties": { "c": { "type": "boolean" } } },
+                            },
+                            "additionalProperties": true,
+                        },
+                    },
+                },
+            }),
+            infer_all(&hints, vec![json!({ "a": [{ "b": { "c": true } }] })])
+        );
+    }
+
+    #[test]
+    fn histograms() {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_histograms(true);
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": {
+                        "type": "uint8",
+                        "nullable": true,
+                        "metadata": {
+                            "histogram": [
+                                { "from": 1.0, "to": 10.0, "count": 2 },
+                                { "from": 10.0, "to": 100.0, "count": 1 },
+                            ],
+                        },
+                    },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "a": 1 }),
+                    json!({ "a": null }),
+                    json!({ "a": 5 }),
+                    json!({ "a": 50 }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        let hints = Hints::default().with_tuples(true);
+
+        // The elements are merged as ever, and each index is described on
+        // its own.
+        assert_eq!(
+            json!({
+                "metadata": {
+                    "tuple": [
+                        { "type": "uint8" },
+                        { "type": "string" },
+                        { "type": "boolean", "nullable": true },
+                    ],
+                },
+                "elements": {},
+            }),
+            infer_all(&hints, vec![json!([1, "a", true]), json!([2, "b", null])])
+        );
+
+        // Arrays of varying length aren't tuples, and nor are empty ones.
+        assert_eq!(
+            json!({ "elements": { "type": "uint8" } }),
+            infer_all(&hints, vec![json!([1, 2]), json!([3, 4]), json!([5])])
+        );
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([])])
+        );
+
+        // Within a tuple, nested arrays and objects are inferred as usual.
+        assert_eq!(
+            json!({
+                "metadata": {
+                    "tuple": [
+                        { "properties": { "a": { "type": "string" } } },
+                        {
+                            "metadata": { "tuple": [{ "type": "uint8" }] },
+                            "elements": { "type": "uint8" },
+                        },
+                    ],
+                },
+                "elements": {},
+            }),
+            infer_all(
+                &hints,
+                vec![json!([{ "a": "x" }, [1]]), json!([{ "a": "y" }, [2]])]
+            )
+        );
+
+        // Without the hint, there's no metadata.
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&Hints::default(), vec![json!([1, "a", true])])
+        );
+    }
+
+    #[test]
+    fn key_descriptions() {
+        let hints = Hints::default()
+            .with_key_descriptions(true)
+            .with_values_collapse(2);
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "createdAt": {
+                        "type": "string",
+                        "metadata": { "description": "Created at" },
+                    },
+                    "scores": {
+                        "values": { "type": "uint8" },
+                        "metadata": { "description": "Scores" },
+                    },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "createdAt": "x", "scores": { "a": 1 } }),
+                    json!({ "createdAt": "y", "scores": { "b": 2 } }),
+                ]
+            )
+        );
+
+        // Properties are described too when they're the variants of a
+        // discriminator.
+        let tag = vec!["kind".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![&tag]),
+        )
+        .with_key_descriptions(true);
+        assert_eq!(
+            json!({
+                "discriminator": "kind",
+                "mapping": {
+                    "a": {
+                        "properties": {
+                            "user_id": {
+                                "type": "uint8",
+                                "metadata": { "description": "User id" },
+                            },
+                        },
+                    },
+                },
+            }),
+            infer_all(&hints, vec![json!({ "kind": "a", "user_id": 1 })])
+        );
+    }
+
+    #[test]
+    fn max_enum_size() {
+        let enum_path = vec![];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_max_enum_size(3);
+
+        assert_eq!(
+            json!({ "enum": ["a", "b", "c"] }),
+            infer_all(&hints, vec![json!("a"), json!("b"), json!("c"), json!("a")])
+        );
+
+        let mut inference = InferredSchema::Unknown;
+        for i in 0..1_000_000 {
+            inference = inference.infer(Value::String(i.to_string()), &hints);
+
+            if let InferredSchema::Enum(ref values) = inference {
+                assert!(values.len() <= 3);
+            }
+        }
+
+        // Once it's fallen back, values already seen don't make it an enum
+        // again.
+        inference = inference.infer(json!("a"), &hints);
+        assert!(matches!(inference, InferredSchema::String));
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            serde_json::to_value(inference.into_schema(&hints).unwrap().into_serde_schema())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn number_type_hints() {
+        let ratio_path = vec!["ratio".to_owned()];
+        let count_path = vec!["counts".to_owned(), "-".to_owned()];
+        let hints = Hints::default().with_number_type_hints(HintMap::new(vec![
+            (&ratio_path, NumType::Float64),
+            (&count_path, NumType::Int8),
+        ]));
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "ratio": { "type": "float64" },
+                    "counts": { "elements": { "type": "int8" } },
+                    "other": { "type": "uint8" },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({ "ratio": 1, "counts": [1, 2], "other": 3 })]
+            )
+        );
+
+        // Numbers that don't fit the hinted type get the narrowest type that
+        // does.
+        assert_eq!(
+            json!({
+                "properties": {
+                    "ratio": { "type": "float64" },
+                    "counts": { "elements": { "type": "uint16" } },
+                    "other": { "type": "uint8" },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({ "ratio": 0.5, "counts": [1, 300], "other": 3 })]
+            )
+        );
+    }
+
+    #[test]
+    fn subtree_number_type_hints() {
+        let payload_path = vec!["payload".to_owned()];
+        let small_path = vec!["payload".to_owned(), "-".to_owned(), "small".to_owned()];
+        let id_path = vec![
+            "payload".to_owned(),
+            "-".to_owned(),
+            "small".to_owned(),
+            "id".to_owned(),
+        ];
+        let hints = Hints::default()
+            .with_subtree_number_type_hints(HintMap::new(vec![
+                (&payload_path, NumType::Uint32),
+                (&small_path, NumType::Int8),
+            ]))
+            .with_number_type_hints(HintMap::new(vec![(&id_path, NumType::Float64)]));
+
+        // The nearest subtree hint wins within a subtree, and a number type
+        // hint wins over any of them. Numbers outside every subtree use the
+        // default number type.
+        let input = json!({
+            "total": 1,
+            "payload": [{ "id": 1, "small": { "count": 1, "id": 1, "deeper": [1] } }],
+        });
+        assert_eq!(
+            json!({
+                "properties": {
+                    "total": { "type": "uint8" },
+                    "payload": {
+                        "elements": {
+                            "properties": {
+                                "id": { "type": "uint32" },
+                                "small": {
+                                    "properties": {
+                                        "count": { "type": "int8" },
+                                        "id": { "type": "float64" },
+                                        "deeper": { "elements": { "type": "int8" } },
+                                    },
+                                },
+                            },
+                        },
+                    },
+                },
+            }),
+            infer_all(&hints, vec![input])
+        );
+
+        // A subtree hint also applies to numbers right at its path, and is
+        // only a preference, like the default number type.
+        let hints = Hints::default()
+            .with_subtree_number_type_hints(HintMap::new(vec![(&payload_path, NumType::Int8)]));
+        assert_eq!(
+            json!({ "properties": { "payload": { "type": "uint8" } } }),
+            infer_all(
+                &hints,
+                vec![json!({ "payload": 1 }), json!({ "payload": 200 })]
+            )
+        );
+
+        let mut warnings = vec![];
+        InferredSchema::Unknown
+            .infer(json!({ "payload": 200 }), &hints)
+            .warnings(&hints, &mut vec![], &mut warnings);
+        assert_eq!(
+            vec![Warning::NumberOutOfRange {
+                path: payload_path.clone(),
+                default: NumType::Int8,
+                min: 200.0,
+                max: 200.0,
+                inferred: Type::Uint8,
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn integer_enums() {
+        let hints = Hints::default().with_integer_enums(3);
+        let repeated = [1, 2, 3, 2, 1, 3, 3].map(|n| json!(n)).to_vec();
+
+        assert_eq!(
+            json!({ "enum": ["1", "2", "3"], "metadata": { "numericEnum": true } }),
+            infer_all(&hints, repeated.clone())
+        );
+
+        assert_eq!(
+            json!({ "elements": { "enum": ["1", "2", "3"], "metadata": { "numericEnum": true } } }),
+            infer_all(&hints, vec![json!([1, 2]), json!([3, 2])])
+        );
+
+        // Without the hint, the same numbers are just numbers.
+        assert_eq!(
+            json!({ "type": "uint8" }),
+            infer_all(&Hints::default(), repeated)
+        );
+
+        // Numbers that never repeat, aren't all integers, or have too many
+        // distinct values aren't taken to be an enum. Every example still
+        // counts towards the number type they're inferred as.
+        assert_eq!(
+            json!({ "type": "int8" }),
+            infer_all(&hints, vec![json!(-1), json!(2), json!(3)])
+        );
+        assert_eq!(
+            json!({ "type": "float64" }),
+            infer_all(&hints, vec![json!(1), json!(1), json!(2.5)])
+        );
+        assert_eq!(
+            json!({
+                "type": "uint8",
+                "metadata": {
+                    "histogram": [{ "from": 1.0, "to": 10.0, "count": 5 }],
+                },
+            }),
+            infer_all(
+                &hints.with_histograms(true),
+                vec![json!(1), json!(2), json!(3), json!(4), json!(1)]
+            )
+        );
+    }
+
+    #[test]
+    fn element_discriminators() {
+        let hints = Hints::default().with_element_discriminators(3);
+        let shapes = json!([
+            { "type": "a", "name": "x", "size": 1 },
+            { "type": "b", "name": "y", "on": true },
+            { "type": "a", "name": "z", "size": 2 },
+        ]);
+
+        assert_eq!(
+            json!({
+                "elements": {
+                    "discriminator": "type",
+                    "mapping": {
+                        "a": {
+                            "properties": {
+                                "name": { "type": "string" },
+                                "size": { "type": "uint8" },
+                            },
+                        },
+                        "b": {
+                            "properties": {
+                                "name": { "type": "string" },
+                                "on": { "type": "boolean" },
+                            },
+                        },
+                    },
+                },
+            }),
+            infer_all(&hints, vec![shapes.clone()])
+        );
+
+        // Without the hint, the elements are merged.
+        assert_eq!(
+            json!({
+                "elements": {
+                    "properties": {
+                        "name": { "type": "string" },
+                        "type": { "type": "string" },
+                    },
+                    "optionalProperties": {
+                        "on": { "type": "boolean" },
+                        "size": { "type": "uint8" },
+                    },
+                },
+            }),
+            infer_all(&Hints::default(), vec![shapes])
+        );
+
+        // There's no tag if the objects don't all have it, if it takes too
+        // many values, or if it only ever takes one.
+        for examples in [
+            vec![json!([{ "type": "a" }, { "kind": "b" }])],
+            vec![json!([{ "type": "a" }, { "type": 1 }])],
+            vec![
+                json!([{ "type": "a" }, { "type": "b" }]),
+                json!([{ "type": "c" }, { "type": "d" }]),
+            ],
+            vec![json!([{ "type": "a", "n": 1 }, { "type": "a" }])],
+        ] {
+            let schema = infer_all(&hints, examples);
+            assert!(
+                schema["elements"].get("discriminator").is_none(),
+                "{}",
+                schema
+            );
+        }
+
+        // Nulls among the elements make the discriminator nullable, and
+        // anything but objects leaves nothing to discriminate.
+        assert_eq!(
+            json!({
+                "elements": {
+                    "discriminator": "type",
+                    "mapping": {
+                        "a": { "properties": { "n": { "type": "uint8" } } },
+                        "b": { "properties": { "s": { "type": "string" } } },
+                    },
+                    "nullable": true,
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!([null, { "type": "a", "n": 1 }, { "type": "b", "s": "x" }])]
+            )
+        );
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([{ "type": "a" }, { "type": "b" }, 1])])
+        );
+
+        // Conflicts are only warned about within the variants.
+        let mut inference = InferredSchema::Unknown;
+        inference = inference.infer(
+            json!([{ "type": "a", "v": 1 }, { "type": "b", "v": "x" }]),
+            &hints,
+        );
+
+        let mut warnings = vec![];
+        inference.warnings(&hints, &mut vec![], &mut warnings);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn nullable_enum() {
+        let enum_path = vec![];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        // Nulls make the enum nullable, wherever they come in the examples,
+        // and are never added to the enum itself.
+        for examples in [
+            vec![json!("a"), json!(null), json!("b"), json!(null)],
+            vec![json!(null), json!("a"), json!("b")],
+            vec![json!("a"), json!("b"), json!(null)],
+        ] {
+            assert_eq!(
+                json!({ "enum": ["a", "b"], "nullable": true }),
+                infer_all(&hints, examples)
+            );
+        }
+
+        assert_eq!(
+            json!({ "enum": ["1", "2"], "nullable": true, "metadata": { "numericEnum": true } }),
+            infer_all(&hints, vec![json!(1), json!(null), json!(2)])
+        );
+
+        // An enum that grows too large is still nullable as a string.
+        assert_eq!(
+            json!({ "type": "string", "nullable": true }),
+            infer_all(
+                &hints.with_max_enum_size(1),
+                vec![json!("a"), json!(null), json!("b")]
+            )
+        );
+
+        let enum_path = vec!["-".to_owned()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        assert_eq!(
+            json!({ "elements": { "enum": ["a", "b"], "nullable": true } }),
+            infer_all(&hints, vec![json!(["a", null]), json!([null, "b"])])
+        );
+    }
+
+    #[test]
+    fn type_hints() {
+        let code_path = vec!["code".to_string()];
+        let score_path = vec!["scores".to_string(), "-".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_type_hints(HintMap::new(vec![
+            (&code_path, Type::String),
+            (&score_path, Type::Float64),
+        ]));
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "code": { "type": "string", "nullable": true },
+                    "scores": { "elements": { "type": "float64" } },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "code": "2020-01-01T00:00:00Z", "scores": [1, 2] }),
+                    json!({ "code": null, "scores": [] }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn type_hint_mismatch() {
+        let code_path = vec!["a".to_string(), "-".to_string(), "code".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_type_hints(HintMap::new(vec![(&code_path, Type::Uint8)]));
+
+        let inference =
+            InferredSchema::Unknown.infer(json!({ "a": [{ "code": 1 }, { "code": 300 }] }), &hints);
+
+        let err = inference.into_schema(&hints).unwrap_err();
+        assert_eq!(
+            InferError::TypeHintMismatch {
+                path: vec!["a".to_string(), "-".to_string(), "code".to_string()],
+                type_: Type::Uint8,
+                inferred: "numbers",
+            },
+            err
+        );
+
+        assert_eq!(
+            "type hint at \"/a/-/code\" is uint8, but examples there were inferred as numbers",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn dates() {
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        // Off by default.
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-01-01")])
+        );
+
+        let hints = hints.with_date_detection(DateDetection::Lenient);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "date" }, "nullable": true }),
+            infer_all(
+                &hints,
+                vec![json!("2020-01-01"), json!(null), json!("2020-13-45")]
+            )
+        );
+
+        // Anything that isn't a date, including timestamps and numeric strings
+        // that aren't shaped like a date, falls back to a plain string.
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(
+                &hints,
+                vec![json!("2020-01-01"), json!("2020-01-01T00:00:00Z")]
+            )
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-01-01"), json!("20200101")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-1-1")])
+        );
+
+        let hints = hints.with_date_detection(DateDetection::Strict);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "date" } }),
+            infer_all(&hints, vec![json!("2020-02-29"), json!("1999-12-31")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-01-01"), json!("2021-02-29")])
+        );
+    }
+
+    #[test]
+    fn emails() {
+        let hints = Hints::default().with_email_detection(true);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "email" }, "nullable": true }),
+            infer_all(
+                &hints,
+                vec![
+                    json!("alice@example.com"),
+                    json!(null),
+                    json!("bob.smith+x@mail.example.org")
+                ]
+            )
+        );
+
+        // Strings that merely contain an @ aren't email addresses.
+        for not_email in [
+            "@example.com",
+            "alice@",
+            "alice@localhost",
+            "alice@example.",
+            "alice@.com",
+            "alice@example..com",
+            "alice@bob@example.com",
+            "alice @example.com",
+            "meet @ example.com",
+        ] {
+            assert_eq!(
+                json!({ "type": "string" }),
+                infer_all(&hints, vec![json!("alice@example.com"), json!(not_email)])
+            );
+        }
+
+        // Email addresses aren't detected unless asked for.
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&Hints::default(), vec![json!("alice@example.com")])
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        let hints = Hints::default();
+
+        assert_eq!(
+            json!({ "type": "timestamp", "nullable": true }),
+            infer_all(
+                &hints,
+                vec![
+                    json!("2020-01-01T00:00:00Z"),
+                    json!(null),
+                    json!("2020-01-01T00:00:00.5+01:00")
+                ]
+            )
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-01-01T00:00:00Z"), json!("x")])
+        );
+
+        // A timestamp hint holds only if every string is a timestamp.
+        let path = ["t".to_string()];
+        let types = HintMap::new(vec![(&path[..], Type::Timestamp)]);
+        let hints = Hints::default().with_type_hints(types);
+        let inference = InferredSchema::Unknown
+            .infer(json!({ "t": "2020-01-01T00:00:00Z" }), &hints)
+            .infer(json!({ "t": "yesterday" }), &hints);
+
+        assert!(matches!(
+            inference.into_schema(&hints),
+            Err(InferError::TypeHintMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn format_sample_size() {
+        // Formats are still detected from more strings than are kept.
+        let hints = Hints::default()
+            .with_date_detection(DateDetection::Lenient)
+            .with_format_sample_size(4);
+        let dates = (0..100).map(|i| json!(format!("2020-01-{:02}", i % 28 + 1)));
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "date" } }),
+            infer_all(&hints, dates.collect())
+        );
+
+        // Up to that many strings, every one counts.
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(
+                &hints,
+                vec![
+                    json!("2020-01-01"),
+                    json!("2020-01-01"),
+                    json!("2020-01-01"),
+                    json!("x")
+                ]
+            )
+        );
+
+        // Timestamps are checked against every string, however few are kept.
+        let timestamps = (0..1000).map(|i| match i {
+            500 => json!("x"),
+            _ => json!("2020-01-01T00:00:00Z"),
+        });
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, timestamps.collect())
+        );
+
+        let hints = Hints::default()
+            .with_date_detection(DateDetection::Lenient)
+            .with_format_sample_size(0);
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("2020-01-01")])
+        );
+        assert_eq!(
+            json!({ "type": "timestamp" }),
+            infer_all(&hints, vec![json!("2020-01-01T00:00:00Z")])
+        );
+
+        // With no format detectors turned on, no strings are kept at all.
+        let inference = InferredSchema::Unknown.infer(json!("x"), &Hints::default());
+        assert!(matches!(
+            inference,
+            InferredSchema::SampledString { samples, .. } if samples.samples().is_empty()
+        ));
+    }
+
+    #[test]
+    fn format_detectors() {
+        struct Prefixed(&'static str);
+
+        impl StringFormatDetector for Prefixed {
+            fn detect(&self, samples: &[&str]) -> Option<String> {
+                if samples.iter().all(|s| s.starts_with(self.0)) {
+                    Some(self.0.to_owned())
+                } else {
+                    None
+                }
+            }
+        }
+
+        let (a, ab) = (Prefixed("a"), Prefixed("ab"));
+        let boolean_strings = BooleanStrings::new(vec!["abc".to_owned()]);
+        let hints = Hints::default()
+            .with_format_detector(&ab)
+            .with_format_detector(&a)
+            .with_boolean_strings(&boolean_strings);
+
+        // Custom detectors come first, in the order they were added.
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "ab" } }),
+            infer_all(&hints, vec![json!("abc")])
+        );
+
+        // A format only applies if every string fits it.
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "a" } }),
+            infer_all(&hints, vec![json!("abc"), json!("ax")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("abc"), json!("ax"), json!("x")])
+        );
+
+        // Detectors see the strings all together, and so can tolerate some
+        // that don't fit.
+        struct MostlyShort;
+
+        impl StringFormatDetector for MostlyShort {
+            fn detect(&self, samples: &[&str]) -> Option<String> {
+                let short = samples.iter().filter(|s| s.len() <= 2).count();
+                if short * 2 > samples.len() {
+                    Some("short".to_owned())
+                } else {
+                    None
+                }
+            }
+        }
+
+        let hints = Hints::default().with_format_detector(&MostlyShort);
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "short" } }),
+            infer_all(&hints, vec![json!("a"), json!("bc"), json!("long")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("a"), json!("long")])
+        );
+    }
+
+    #[test]
+    fn content_encodings() {
+        let hints = Hints::default().with_content_encodings(16);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "contentEncoding": "base16" } }),
+            infer_all(
+                &hints,
+                vec![json!("00deadbeef112233"), json!("0123456789abcdef0123")]
+            )
+        );
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "contentEncoding": "base64" } }),
+            infer_all(
+                &hints,
+                vec![
+                    json!("SGVsbG8sIHdvcmxkIQ=="),
+                    json!("AAECAwQFBgcICQoLDA0ODw==")
+                ]
+            )
+        );
+
+        // With a ratio, a few strings that aren't encoded are tolerated.
+        let mostly_hex = vec![
+            json!("00deadbeef112233"),
+            json!("0123456789abcdef0123"),
+            json!("none"),
+        ];
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, mostly_hex.clone())
+        );
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "contentEncoding": "base16" } }),
+            infer_all(
+                &Hints::default()
+                    .with_content_encoding_ratio(0.5)
+                    .with_content_encodings(16),
+                mostly_hex
+            )
+        );
+
+        // Short strings, like this one that's valid base64, are ignored.
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("SGVsbG8sIHdvcmxkIQ=="), json!("Abcd")])
+        );
+
+        // An encoding is recorded under its own key, and so it doesn't
+        // compete with other formats.
+        struct Hex;
+
+        impl StringFormatDetector for Hex {
+            fn detect(&self, samples: &[&str]) -> Option<String> {
+                if samples
+                    .iter()
+                    .all(|s| s.bytes().all(|b| b.is_ascii_hexdigit()))
+                {
+                    Some("hex".to_owned())
+                } else {
+                    None
+                }
+            }
+        }
+
+        assert_eq!(
+            json!({
+                "type": "string",
+                "metadata": { "format": "hex", "contentEncoding": "base16" },
+            }),
+            infer_all(
+                &hints.with_format_detector(&Hex),
+                vec![json!("00deadbeef112233")]
+            )
+        );
+    }
+
+    #[test]
+    fn boolean_strings() {
+        let boolean_strings = BooleanStrings::default();
+        let hints = Hints::default().with_boolean_strings(&boolean_strings);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "boolean" } }),
+            infer_all(&hints, vec![json!("yes"), json!("no"), json!("true")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("yes"), json!("True")])
+        );
+
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&hints, vec![json!("1"), json!("0")])
+        );
+
+        let boolean_strings = BooleanStrings::default().with_ignore_case(true);
+        let hints = Hints::default().with_boolean_strings(&boolean_strings);
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "format": "boolean" }, "nullable": true }),
+            infer_all(&hints, vec![json!("yes"), json!("True"), json!(null)])
+        );
+    }
+
+    #[test]
+    fn mixed_scalars() {
+        let hints = Hints::default();
+
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([1, "a", true])])
+        );
+
+        let hints = Hints::default().with_mixed_scalars(MixedScalars::String);
+
+        assert_eq!(
+            json!({ "elements": { "type": "string", "nullable": true } }),
+            infer_all(&hints, vec![json!([1, "a", null, true])])
+        );
+
+        assert_eq!(
+            json!({ "elements": { "type": "string" } }),
+            infer_all(&hints, vec![json!(["2020-01-01T00:00:00Z", 1.5])])
+        );
+
+        // Arrays and objects still make for the empty form.
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([1, "a", [true]])])
+        );
+
+        assert_eq!(
+            json!({ "elements": {} }),
+            infer_all(&hints, vec![json!([{}, 1])])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn pattern_hints() {
+        let values_path = vec![r"{user_\d+}".to_owned(), "tags".to_owned()];
+        let num_type_path = vec![r"{user_\d+}".to_owned(), "score".to_owned()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![]),
+        )
+        .with_number_type_hints(HintMap::new(vec![(&num_type_path, NumType::Float64)]));
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "user_1": {
+                        "properties": {
+                            "score": { "type": "float64" },
+                            "tags": { "values": { "type": "boolean" } },
+                        },
+                    },
+                    "user_22": {
+                        "properties": {
+                            "score": { "type": "float64" },
+                            "tags": { "values": { "type": "boolean" } },
+                        },
+                    },
+                    "admin": {
+                        "properties": {
+                            "score": { "type": "uint8" },
+                            "tags": { "properties": { "a": { "type": "boolean" } } },
+                        },
+                    },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({
+                    "user_1": { "score": 1, "tags": { "a": true } },
+                    "user_22": { "score": 2, "tags": { "b": false } },
+                    "admin": { "score": 3, "tags": { "a": true } },
+                })]
+            )
+        );
+    }
+
+    #[test]
+    fn non_finite_numbers() {
+        let hints = Hints::default();
+        let inference = InferredSchema::Unknown.infer(json!({ "a": 1, "b": 2 }), &hints);
+        let inference = match inference {
+            InferredSchema::Properties {
+                mut required,
+                optional,
+                objects,
+            } => {
+                let a = InferredNumber::new().infer(1.0).infer(f64::INFINITY);
+                required.insert("a".to_owned(), InferredSchema::Number(a));
+                InferredSchema::Properties {
+                    required,
+                    optional,
+                    objects,
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        let mut warnings = vec![];
+        inference.warnings(&hints, &mut vec![], &mut warnings);
+        assert_eq!(
+            vec![Warning::NonFiniteNumber {
+                path: vec!["a".to_owned()]
+            }],
+            warnings
+        );
+        assert_eq!(
+            r#"numbers at "/a" included NaN or infinity, which JSON can't represent, so they were inferred as float64"#,
+            warnings[0].to_string()
+        );
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "type": "float64" },
+                    "b": { "type": "uint8" },
+                },
+            }),
+            serde_json::to_value(inference.into_schema(&hints).unwrap().into_serde_schema())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn big_integers() {
+        let big: Value = serde_json::from_str("123456789012345678901234567890").unwrap();
+        let inputs = vec![json!({ "id": 1 }), json!({ "id": big })];
+
+        let mut inference = InferredSchema::Unknown;
+        for input in inputs.clone() {
+            inference = inference.infer(input, &Hints::default());
+        }
+
+        let mut warnings = vec![];
+        inference.warnings(&Hints::default(), &mut vec![], &mut warnings);
+        assert_eq!(
+            Warning::BigInteger {
+                path: vec!["id".to_owned()]
+            },
+            warnings[0]
+        );
+
+        assert_eq!(
+            json!({ "properties": { "id": { "type": "float64" } } }),
+            serde_json::to_value(
+                inference
+                    .into_schema(&Hints::default())
+                    .unwrap()
+                    .into_serde_schema()
+            )
+            .unwrap()
+        );
+
+        let hints = Hints::default().with_big_integers(BigIntegers::String);
+        assert_eq!(
+            json!({
+                "properties": {
+                    "id": { "type": "string", "metadata": { "bigint": true } },
+                },
+            }),
+            infer_all(&hints, inputs.clone())
+        );
+
+        // Integers that fit in 64 bits are numbers as usual, and big ones in
+        // an integer enum fall out of it.
+        assert_eq!(
+            json!({ "type": "float64" }),
+            infer_all(&hints, vec![json!(u64::MAX)])
+        );
+
+        assert_eq!(
+            json!({ "type": "string", "metadata": { "bigint": true } }),
+            infer_all(&hints.with_integer_enums(3), vec![json!(1), json!(1), big])
+        );
+    }
+
+    #[test]
+    fn values_collapse() {
+        let hints = Hints::default().with_values_collapse(3);
+        let inputs = vec![
+            json!({ "2020-01-01": [1], "2020-01-02": [2, 3] }),
+            json!({ "2020-01-01": [4], "2020-01-03": [6] }),
+            json!({ "2020-01-01": [], "2020-01-04": [5] }),
+        ];
+
+        let mut inference = InferredSchema::Unknown;
+        for input in inputs.clone() {
+            inference = inference.infer(input, &hints);
+        }
+
+        // A required property with the same schema doesn't get in the way.
+        let schema = inference.into_schema(&hints).unwrap();
+        assert_eq!(
+            json!({ "values": { "elements": { "type": "uint8" } } }),
+            serde_json::to_value(schema.clone().into_serde_schema()).unwrap()
+        );
+
+        // The collapsed schema still accepts every example.
+        for input in &inputs {
+            assert!(crate::schema_accepts(&schema, input), "{}", input);
+        }
+
+        // Too few optional properties.
+        assert_eq!(
+            json!({ "optionalProperties": { "a": { "type": "uint8" }, "b": { "type": "uint8" } } }),
+            infer_all(&hints, vec![json!({ "a": 1 }), json!({ "b": 2 })])
+        );
+
+        // Properties with different schemas.
+        assert_eq!(
+            json!({
+                "optionalProperties": {
+                    "a": { "type": "uint8" },
+                    "b": { "type": "uint8" },
+                    "c": { "type": "string" },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({ "a": 1 }), json!({ "b": 2 }), json!({ "c": "x" })]
+            )
+        );
+
+        // Nullable objects stay nullable.
+        assert_eq!(
+            json!({ "values": { "type": "uint8" }, "nullable": true }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "a": 1 }),
+                    json!(null),
+                    json!({ "b": 2 }),
+                    json!({ "c": 3 })
+                ]
+            )
+        );
+
+        // The variants of a discriminator have to stay properties.
+        let discriminator_path = vec!["type".to_owned()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![&discriminator_path]),
+        )
+        .with_values_collapse(1);
+
+        assert_eq!(
+            json!({
+                "discriminator": "type",
+                "mapping": {
+                    "a": { "optionalProperties": { "x": { "type": "uint8" } } },
+                },
+            }),
+            infer_all(
+                &hints,
+                vec![json!({ "type": "a", "x": 1 }), json!({ "type": "a" })]
+            )
+        );
+    }
+
+    #[test]
+    fn absent_as_nullable() {
+        // The quick-start example from the README, with one property left out.
+        let inputs = vec![
+            json!({ "name": "Joe", "age": 42 }),
+            json!({ "name": "Jane" }),
+        ];
+
+        assert_eq!(
+            json!({
+                "properties": { "name": { "type": "string" } },
+                "optionalProperties": { "age": { "type": "uint8" } },
+            }),
+            infer_all(&Hints::default(), inputs.clone())
+        );
+
+        let hints = Hints::default().with_absent_as_nullable(true);
+        assert_eq!(
+            json!({
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "uint8", "nullable": true },
+                },
+            }),
+            infer_all(&hints, inputs)
+        );
+
+        // Properties that were already nullable, or that only ever saw nulls,
+        // stay as they were.
+        assert_eq!(
+            json!({ "properties": { "a": { "type": "uint8", "nullable": true }, "b": {} } }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({}),
+                    json!({ "a": null, "b": null }),
+                    json!({ "a": 1 })
+                ]
+            )
+        );
+
+        // Without nullable, properties are left optional.
+        let hints = hints.with_emit_nullable(false);
+        assert_eq!(
+            json!({ "optionalProperties": { "a": { "type": "uint8" } } }),
+            infer_all(&hints, vec![json!({}), json!({ "a": 1 })])
+        );
+    }
+
+    #[test]
+    fn case_insensitive_keys() {
+        let hints = Hints::default();
+
+        assert_eq!(
+            json!({ "properties": { "userId": { "type": "uint8" }, "userid": { "type": "uint8" } } }),
+            infer_all(&hints, vec![json!({ "userId": 1, "userid": 2 })])
+        );
+
+        let hints = Hints::default().with_case_insensitive_keys(true);
+
+        // The first spelling seen is the one that's kept, and the values of
+        // every spelling are merged as usual.
+        assert_eq!(
+            json!({ "properties": { "userId": { "type": "uint16" } } }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "userId": 1 }),
+                    json!({ "userid": 2 }),
+                    json!({ "UserID": 300 }),
+                ]
+            )
+        );
+
+        assert_eq!(
+            json!({
+                "properties": { "userId": {} },
+                "optionalProperties": { "Name": { "type": "string" } },
+            }),
+            infer_all(
+                &hints,
+                vec![
+                    json!({ "userId": 1, "userid": "x" }),
+                    json!({ "userid": null, "Name": "a" }),
+                    json!({ "UserId": true, "name": "b" }),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_as_number() {
+        let hints = Hints::new(
+            NumType::Int32,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        let values = vec![
+            json!({ "a": null, "b": [], "c": 1, "d": 1 }),
+            json!({ "a": null, "b": [], "c": 2, "d": "x" }),
+        ];
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": {},
+                    "b": { "elements": {} },
+                    "c": { "type": "int32" },
+                    "d": {},
+                },
+            }),
+            infer_all(&hints, values.clone())
+        );
+
+        // Only the positions that never saw anything but nulls change. Mixed
+        // positions are still the empty form.
+        let hints = hints.with_unknown_as_number(true);
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "type": "int32", "nullable": true },
+                    "b": { "elements": { "type": "int32" } },
+                    "c": { "type": "int32" },
+                    "d": {},
+                },
+            }),
+            infer_all(&hints, values)
+        );
+    }
+
+    #[test]
+    fn empty_fallback() {
+        let values = vec![
+            json!({ "a": null, "b": [], "c": 1, "d": 1 }),
+            json!({ "a": null, "b": [], "c": 2, "d": "x" }),
+        ];
+
+        let hints = Hints::default().with_empty_fallback(EmptyFallback::Unknown);
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "metadata": { "unknown": true } },
+                    "b": { "elements": { "metadata": { "unknown": true } } },
+                    "c": { "type": "uint8" },
+                    "d": {},
+                },
+            }),
+            infer_all(&hints, values.clone())
+        );
+
+        // The fallback is only for positions with nothing to go on, and so a
+        // position that later sees data is inferred from it as usual.
+        assert_eq!(
+            json!({ "properties": { "a": { "type": "string", "nullable": true } } }),
+            infer_all(&hints, vec![json!({ "a": null }), json!({ "a": "x" })])
+        );
+
+        let hints = Hints::default().with_empty_fallback(EmptyFallback::Number);
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "type": "uint8", "nullable": true },
+                    "b": { "elements": { "type": "uint8" } },
+                    "c": { "type": "uint8" },
+                    "d": {},
+                },
+            }),
+            infer_all(&hints, values)
+        );
+    }
+
+    #[test]
+    fn number_out_of_range_warnings() {
+        let num_type_path = vec!["b".to_owned()];
+        let type_path = vec!["c".to_owned()];
+        let hints = Hints::default()
+            .with_number_type_hints(HintMap::new(vec![(&num_type_path, NumType::Int32)]))
+            .with_type_hints(HintMap::new(vec![(&type_path, Type::Float64)]));
+
+        let mut inference = InferredSchema::Unknown;
+        for value in [
+            json!({ "a": [1, 500], "b": 70000, "c": 1.5, "d": 3, "e": null }),
+            json!({ "a": [-2], "b": 1, "c": 1e300, "d": 4, "e": 0.5 }),
+        ] {
+            inference = inference.infer(value, &hints);
+        }
+
+        let mut warnings = vec![];
+        inference.warnings(&hints, &mut vec![], &mut warnings);
+
+        // Numbers are checked against the type tried first for them, and not
+        // at all if they have a type hint.
+        assert_eq!(
+            vec![
+                Warning::NumberOutOfRange {
+                    path: vec!["a".to_owned(), "-".to_owned()],
+                    default: NumType::Uint8,
+                    min: -2.0,
+                    max: 500.0,
+                    inferred: Type::Int16,
+                },
+                Warning::NumberOutOfRange {
+                    path: vec!["e".to_owned()],
+                    default: NumType::Uint8,
+                    min: 0.5,
+                    max: 0.5,
+                    inferred: Type::Float64,
+                },
+            ],
+            warnings
+        );
+
+        assert_eq!(
+            "numbers at \"/a/-\" ranged from -2 to 500, which don't all fit in uint8, so they were inferred as int16",
+            warnings[0].to_string()
+        );
+    }
+
+    #[test]
+    fn enum_order() {
+        let root = vec![];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&root]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        let cases = vec![
+            (
+                vec![json!("b"), json!("a"), json!("C"), json!("aa"), json!("B")],
+                r#"{"enum":["B","C","a","aa","b"]}"#,
+            ),
+            (
+                vec![json!(10), json!(9), json!(-1), json!(2)],
+                r#"{"metadata":{"numericEnum":true},"enum":["-1","10","2","9"]}"#,
+            ),
+        ];
+
+        for (values, expected) in cases {
+            for n in 0..values.len() {
+                for reverse in [false, true] {
+                    let mut values = values.clone();
+                    values.rotate_left(n);
+                    if reverse {
+                        values.reverse();
+                    }
+
+                    let mut inference = InferredSchema::Unknown;
+                    for value in values {
+                        inference = inference.infer(value, &hints);
+                    }
+
+                    let schema = inference.into_schema(&hints).unwrap().into_serde_schema();
+                    assert_eq!(expected, serde_json::to_string(&schema).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn root_hints() {
+        let root = vec![];
+        let tag = vec!["type".to_string()];
+        let elements = vec!["-".to_string()];
+        let hints = |enums, values, discriminator| {
+            Hints::new(
+                NumType::Uint8,
+                HintSet::new(enums),
+                HintSet::new(values),
+                HintSet::new(discriminator),
+            )
+        };
+        let errors = |hints: &Hints, value: Value| {
+            InferredSchema::Unknown
+                .infer(value, hints)
+                .into_schema(hints)
+                .unwrap_err()
+        };
+
+        // An enum hint at the root makes an enum of root strings or integers.
+        let enums = hints(vec![&root], vec![], vec![]);
+        assert_eq!(
+            json!({ "enum": ["a", "b"], "nullable": true }),
+            infer_all(&enums, vec![json!("a"), json!("b"), json!(null)])
+        );
+        assert_eq!(
+            json!({ "enum": ["1", "2"], "metadata": { "numericEnum": true } }),
+            infer_all(&enums, vec![json!(1), json!(2)])
+        );
+        assert_eq!(
+            InferError::EnumHintMismatch {
+                path: vec![],
+                inferred: "objects"
+            },
+            errors(&enums, json!({ "a": "x" }))
+        );
+        assert_eq!(
+            InferError::EnumHintMismatch {
+                path: vec!["-".to_owned()],
+                inferred: "booleans"
+            },
+            errors(&hints(vec![&elements], vec![], vec![]), json!([true, null]))
+        );
+
+        // A values hint at the root makes a map of a root object, and is
+        // ignored for anything else.
+        let values = hints(vec![], vec![&root], vec![]);
+        assert_eq!(
+            json!({ "values": { "type": "uint8" } }),
+            infer_all(&values, vec![json!({ "a": 1 }), json!({ "b": 2 })])
+        );
+        assert_eq!(
+            json!({ "type": "string" }),
+            infer_all(&values, vec![json!("a")])
+        );
+
+        // A discriminator at the root is hinted by the path to its tag. The
+        // root itself has no tag to go on.
+        assert_eq!(
+            json!({ "discriminator": "type", "mapping": { "a": {} } }),
+            infer_all(
+                &hints(vec![], vec![], vec![&tag]),
+                vec![json!({ "type": "a" })]
+            )
+        );
+        let discriminator = hints(vec![], vec![], vec![&root]);
+        assert_eq!(
+            InferError::DiscriminatorHintWithoutTag { path: vec![] },
+            errors(&discriminator, json!({ "type": "a" }))
+        );
+        assert_eq!(
+            "discriminator hint at \"\" doesn't name a tag; point it to the tag property instead, such as \"/type\"",
+            errors(&discriminator, json!({ "type": "a" })).to_string()
+        );
+    }
+
+    #[test]
+    fn conflicts_commute() {
+        let hints = Hints::default();
+        let collisions = vec![
+            (vec![json!(1), json!(true)], vec!["booleans", "numbers"]),
+            (vec![json!("a"), json!(1.5)], vec!["numbers", "strings"]),
+            (
+                vec![json!({ "a": 1 }), json!([1])],
+                vec!["arrays", "objects"],
+            ),
+            (
+                vec![json!("2020-01-01T00:00:00Z"), json!(1)],
+                vec!["numbers", "strings"],
+            ),
+            (
+                vec![json!(1), json!(true), json!(null), json!("a")],
+                vec!["booleans", "numbers", "strings"],
+            ),
+        ];
+
+        for (values, kinds) in collisions {
+            // Every rotation of the examples, forwards and backwards.
+            for n in 0..values.len() {
+                for reverse in [false, true] {
+                    let mut values = values.clone();
+                    values.rotate_left(n);
+                    if reverse {
+                        values.reverse();
+                    }
+
+                    let mut inference = InferredSchema::Unknown;
+                    for value in values.clone() {
+                        inference = inference.infer(json!({ "x": value }), &hints);
+                    }
+
+                    let mut warnings = vec![];
+                    inference.warnings(&hints, &mut vec![], &mut warnings);
+                    assert_eq!(
+                        vec![Warning::TypeConflict {
+                            path: vec!["x".to_owned()],
+                            kinds: kinds.clone(),
+                        }],
+                        warnings,
+                        "{:?}",
+                        values
+                    );
+
+                    let schema = inference.into_schema(&hints).unwrap().into_serde_schema();
+                    assert_eq!(
+                        json!({ "properties": { "x": {} } }),
+                        serde_json::to_value(schema).unwrap(),
+                        "{:?}",
+                        values
+                    );
+                }
+            }
+        }
+
+        assert_eq!(
+            "examples at \"/x\" were a mix of booleans, numbers and strings, so any value is accepted there",
+            Warning::TypeConflict {
+                path: vec!["x".to_owned()],
+                kinds: vec!["booleans", "numbers", "strings"],
+            }
+            .to_string()
+        );
+
+        // Warnings made by hand with fewer than two kinds don't panic.
+        assert_eq!(
+            "examples at \"/x\" were all numbers, so any value is accepted there",
+            Warning::TypeConflict {
+                path: vec!["x".to_owned()],
+                kinds: vec!["numbers"],
+            }
+            .to_string()
+        );
+
+        assert_eq!(
+            "examples at \"/x\" were of kinds that conflict, so any value is accepted there",
+            Warning::TypeConflict {
+                path: vec!["x".to_owned()],
+                kinds: vec![],
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn warnings() {
+        let hints = Hints::default();
+
+        let mut inference = InferredSchema::Unknown;
+        for value in [
+            json!({ "x": 5, "y": [true], "z": "a" }),
+            json!({ "x": { "a": 1 }, "y": [1, "b"], "z": "b" }),
+            json!({ "x": "c", "y": [] }),
+        ] {
+            inference = inference.infer(value, &hints);
+        }
+
+        let mut warnings = vec![];
+        inference.warnings(&hints, &mut vec![], &mut warnings);
+
+        assert_eq!(
+            vec![
+                Warning::TypeConflict {
+                    path: vec!["x".to_owned()],
+                    kinds: vec!["numbers", "objects", "strings"],
+                },
+                Warning::TypeConflict {
+                    path: vec!["y".to_owned(), "-".to_owned()],
+                    kinds: vec!["booleans", "numbers", "strings"],
+                },
+            ],
+            warnings
+        );
+
+        // The schema is still produced, with the empty form where there were
+        // conflicts.
+        assert_eq!(
+            json!({
+                "properties": {
+                    "x": {},
+                    "y": { "elements": {} },
+                },
+                "optionalProperties": {
+                    "z": { "type": "string" },
+                },
+            }),
+            serde_json::to_value(inference.into_schema(&hints).unwrap().into_serde_schema())
+                .unwrap()
+        );
+    }
+}
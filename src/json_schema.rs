@@ -0,0 +1,349 @@
+use jtd::{Schema, Type};
+use serde_json::{json, Map, Value};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Converts a JSON Typedef schema into an equivalent JSON Schema (draft
+/// 2020-12) document.
+///
+/// Most of JSON Typedef maps directly onto JSON Schema. The constructs that
+/// don't are approximated as follows:
+///
+/// * `timestamp` becomes a string with `"format": "date-time"`. JSON Schema
+///   treats `format` as an annotation by default, so validators may not check
+///   it.
+/// * The integer types become `integer` with a `minimum` and `maximum`, and
+///   both `float32` and `float64` become `number`. The distinction between the
+///   two float types is lost.
+/// * `nullable` becomes a `null` member in `type` (or in `enum`), or an `anyOf`
+///   with `{ "type": "null" }` for forms without either of those keywords.
+/// * `discriminator` becomes a `oneOf` over the mapping, where each member
+///   requires the discriminator property to be `const` the mapping's key.
+/// * `definitions` and `ref` become `$defs` and `$ref`.
+/// * Of `metadata`, only `description` is carried over. Everything else is
+///   dropped.
+///
+/// ```
+/// use serde_json::json;
+/// use jtd::Schema;
+/// use jtd_infer::to_json_schema;
+///
+/// let schema = Schema::from_serde_schema(serde_json::from_value(json!({
+///     "properties": { "name": { "type": "string", "nullable": true } },
+/// })).unwrap()).unwrap();
+///
+/// assert_eq!(
+///     json!({
+///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+///         "type": "object",
+///         "properties": { "name": { "type": ["string", "null"] } },
+///         "required": ["name"],
+///         "additionalProperties": false,
+///     }),
+///     to_json_schema(&schema),
+/// );
+/// ```
+pub fn to_json_schema(schema: &Schema) -> Value {
+    let mut root = Map::new();
+    root.insert("$schema".to_owned(), DIALECT.into());
+
+    if !schema.definitions().is_empty() {
+        let defs = schema
+            .definitions()
+            .iter()
+            .map(|(name, sub_schema)| (name.clone(), convert(sub_schema)))
+            .collect();
+
+        root.insert("$defs".to_owned(), Value::Object(defs));
+    }
+
+    if let Value::Object(converted) = convert(schema) {
+        root.extend(converted);
+    }
+
+    Value::Object(root)
+}
+
+fn convert(schema: &Schema) -> Value {
+    let mut out = match schema {
+        Schema::Empty { .. } => json!({}),
+        Schema::Ref { ref_, .. } => json!({ "$ref": format!("#/$defs/{}", ref_) }),
+        Schema::Type { type_, .. } => convert_type(type_),
+        Schema::Enum { enum_, .. } => json!({ "enum": enum_ }),
+        Schema::Elements { elements, .. } => json!({
+            "type": "array",
+            "items": convert(elements),
+        }),
+        Schema::Properties {
+            properties,
+            optional_properties,
+            additional_properties,
+            ..
+        } => convert_properties(
+            properties.iter().map(|(k, v)| (k.clone(), convert(v))),
+            optional_properties
+                .iter()
+                .map(|(k, v)| (k.clone(), convert(v))),
+            *additional_properties,
+        ),
+        Schema::Values { values, .. } => json!({
+            "type": "object",
+            "additionalProperties": convert(values),
+        }),
+        Schema::Discriminator {
+            discriminator,
+            mapping,
+            ..
+        } => {
+            let one_of: Vec<_> = mapping
+                .iter()
+                .map(|(tag, sub_schema)| {
+                    let (properties, optional_properties, additional_properties) = match sub_schema
+                    {
+                        Schema::Properties {
+                            properties,
+                            optional_properties,
+                            additional_properties,
+                            ..
+                        } => (properties, optional_properties, *additional_properties),
+                        _ => unreachable!("non-properties mapping"),
+                    };
+
+                    let tag_schema = (discriminator.clone(), json!({ "const": tag }));
+                    convert_properties(
+                        std::iter::once(tag_schema)
+                            .chain(properties.iter().map(|(k, v)| (k.clone(), convert(v)))),
+                        optional_properties
+                            .iter()
+                            .map(|(k, v)| (k.clone(), convert(v))),
+                        additional_properties,
+                    )
+                })
+                .collect();
+
+            json!({ "type": "object", "oneOf": one_of })
+        }
+    };
+
+    if let Some(description) = schema.metadata().get("description") {
+        out["description"] = description.clone();
+    }
+
+    // The jtd crate reports the empty form as nullable, but it already accepts
+    // null as-is.
+    if schema.nullable() && !matches!(schema, Schema::Empty { .. }) {
+        out = make_nullable(out);
+    }
+
+    out
+}
+
+fn convert_type(type_: &Type) -> Value {
+    let int_range =
+        |min: i64, max: i64| json!({ "type": "integer", "minimum": min, "maximum": max });
+
+    match type_ {
+        Type::Boolean => json!({ "type": "boolean" }),
+        Type::Float32 | Type::Float64 => json!({ "type": "number" }),
+        Type::Int8 => int_range(i8::MIN.into(), i8::MAX.into()),
+        Type::Uint8 => int_range(u8::MIN.into(), u8::MAX.into()),
+        Type::Int16 => int_range(i16::MIN.into(), i16::MAX.into()),
+        Type::Uint16 => int_range(u16::MIN.into(), u16::MAX.into()),
+        Type::Int32 => int_range(i32::MIN.into(), i32::MAX.into()),
+        Type::Uint32 => int_range(u32::MIN.into(), u32::MAX.into()),
+        Type::String => json!({ "type": "string" }),
+        Type::Timestamp => json!({ "type": "string", "format": "date-time" }),
+    }
+}
+
+fn convert_properties(
+    required: impl Iterator<Item = (String, Value)>,
+    optional: impl Iterator<Item = (String, Value)>,
+    additional_properties: bool,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required_keys = Vec::new();
+
+    for (k, v) in required {
+        required_keys.push(Value::String(k.clone()));
+        properties.insert(k, v);
+    }
+
+    properties.extend(optional);
+
+    let mut out = json!({ "type": "object", "properties": properties });
+    if !required_keys.is_empty() {
+        out["required"] = Value::Array(required_keys);
+    }
+
+    if !additional_properties {
+        out["additionalProperties"] = Value::Bool(false);
+    }
+
+    out
+}
+
+fn make_nullable(mut schema: Value) -> Value {
+    // The members of a oneOf would reject null regardless of what the
+    // top-level type says.
+    if schema.get("oneOf").is_some() {
+        return json!({ "anyOf": [schema, { "type": "null" }] });
+    }
+
+    if let Some(Value::String(type_)) = schema.get("type") {
+        schema["type"] = json!([type_, "null"]);
+        return schema;
+    }
+
+    if let Some(Value::Array(members)) = schema.get_mut("enum") {
+        members.push(Value::Null);
+        return schema;
+    }
+
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert_serde(schema: Value) -> Value {
+        to_json_schema(&Schema::from_serde_schema(serde_json::from_value(schema).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(
+            json!({ "$schema": DIALECT, "type": "integer", "minimum": 0, "maximum": 255 }),
+            convert_serde(json!({ "type": "uint8" }))
+        );
+
+        assert_eq!(
+            json!({ "$schema": DIALECT, "type": ["string", "null"], "format": "date-time" }),
+            convert_serde(json!({ "type": "timestamp", "nullable": true }))
+        );
+
+        assert_eq!(
+            json!({ "$schema": DIALECT, "enum": ["a", "b", null] }),
+            convert_serde(json!({ "enum": ["a", "b"], "nullable": true }))
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(
+            json!({ "$schema": DIALECT, "type": ["array", "null"], "items": {} }),
+            convert_serde(json!({ "elements": {}, "nullable": true }))
+        );
+
+        assert_eq!(
+            json!({
+                "$schema": DIALECT,
+                "type": "object",
+                "additionalProperties": { "type": "boolean" },
+            }),
+            convert_serde(json!({ "values": { "type": "boolean" } }))
+        );
+
+        assert_eq!(
+            json!({
+                "$schema": DIALECT,
+                "type": "object",
+                "properties": {
+                    "a": { "type": "string" },
+                    "b": { "type": "string" },
+                },
+                "required": ["a"],
+            }),
+            convert_serde(json!({
+                "properties": { "a": { "type": "string" } },
+                "optionalProperties": { "b": { "type": "string" } },
+                "additionalProperties": true,
+            }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            json!({
+                "$schema": DIALECT,
+                "type": "object",
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "type": { "const": "n" },
+                            "value": { "type": "number" },
+                        },
+                        "required": ["type", "value"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "type": { "const": "s" },
+                        },
+                        "required": ["type"],
+                        "additionalProperties": false,
+                    },
+                ],
+            }),
+            convert_serde(json!({
+                "discriminator": "type",
+                "mapping": {
+                    "n": { "properties": { "value": { "type": "float64" } } },
+                    "s": { "properties": {} },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn nullable_discriminator() {
+        assert_eq!(
+            json!({
+                "$schema": DIALECT,
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "oneOf": [
+                            {
+                                "type": "object",
+                                "properties": { "type": { "const": "s" } },
+                                "required": ["type"],
+                                "additionalProperties": false,
+                            },
+                        ],
+                    },
+                    { "type": "null" },
+                ],
+            }),
+            convert_serde(json!({
+                "discriminator": "type",
+                "mapping": { "s": { "properties": {} } },
+                "nullable": true,
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions() {
+        assert_eq!(
+            json!({
+                "$schema": DIALECT,
+                "$defs": { "name": { "type": "string", "description": "A name" } },
+                "type": "array",
+                "items": {
+                    "anyOf": [{ "$ref": "#/$defs/name" }, { "type": "null" }],
+                },
+            }),
+            convert_serde(json!({
+                "definitions": {
+                    "name": { "type": "string", "metadata": { "description": "A name" } },
+                },
+                "elements": { "ref": "name", "nullable": true },
+            }))
+        );
+    }
+}
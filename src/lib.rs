@@ -29,8 +29,6 @@
 //! inferrer = inferrer.infer(json!({ "foo": true, "bar": "xxx" }));
 //! inferrer = inferrer.infer(json!({ "foo": false, "bar": null, "baz": 5 }));
 //!
-//! let inference = inferrer.into_schema();
-//!
 //! assert_eq!(
 //!     json!({
 //!         "properties": {
@@ -41,122 +39,108 @@
 //!             "baz": { "type": "uint8" },
 //!         },
 //!     }),
-//!     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+//!     inferrer.into_json().unwrap(),
 //! )
 //! ```
+//!
+//! [`Inferrer::into_json`] gives the schema as a [`serde_json::Value`]. To work
+//! with it as a [`jtd::Schema`] instead, use [`Inferrer::into_schema`].
+//!
+//! If you don't need any hints, `Hints::default()` is a shorthand for the hints
+//! above. See [`Hints`] for the hints you can give.
+//!
+//! # Features
+//!
+//! Inference itself doesn't need anything from the `jtd-infer` command, or from
+//! the WebAssembly bindings. Both are built by default, behind the `cli` and
+//! `wasm` features, though the bindings are only ever built for `wasm32`. To
+//! depend on just the library, turn them off:
+//!
+//! ```toml
+//! [dependencies]
+//! jtd_infer = { version = "0.2", default-features = false }
+//! ```
+//!
+//! Of the other features, `regex` allows patterns in hint paths, as described
+//! at [`HintSet::new`], and `futures` adds `Inferrer::infer_stream`. The rest
+//! only add input formats to the command.
 
+mod accepts;
+mod config;
+mod describe;
+mod duplicate_keys;
+mod error;
+mod format;
 mod hints;
+mod inference;
 mod inferred_number;
 mod inferred_schema;
+mod json_schema;
+mod reservoir;
+mod root_name;
+mod rust;
+mod stamp;
+mod stats;
+mod warning;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
 
-pub use crate::hints::{HintSet, Hints};
+pub use crate::accepts::schema_accepts;
+pub use crate::config::Config;
+pub use crate::duplicate_keys::CheckedValue;
+pub use crate::error::{to_json_pointer, InferError};
+pub use crate::format::StringFormatDetector;
+pub use crate::hints::{
+    BigIntegers, BooleanStrings, DateDetection, EmptyFallback, HintMap, HintSet, Hints,
+    MixedScalars, NullableFallback,
+};
+pub use crate::inference::Inference;
 pub use crate::inferred_number::NumType;
+pub use crate::inferred_schema::InferredKind;
 use crate::inferred_schema::InferredSchema;
+pub use crate::json_schema::to_json_schema;
+pub use crate::root_name::name_root;
+pub use crate::rust::to_rust;
+pub use crate::stats::{PositionStats, Stats};
+pub use crate::warning::Warning;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use crate::wasm::{generate_schema, generate_schema_with_warnings, SchemaParams};
 use jtd::Schema;
-use serde_json::Value;
-
-use anyhow::Error;
-use clap::{crate_version, load_yaml, App, AppSettings};
 use serde_json::Deserializer;
-use std::fs::File;
-use std::io::stdin;
-use std::io::BufReader;
+use serde_json::Value;
+use std::collections::BTreeSet;
 use std::io::Read;
-use std::io::Cursor;
-
-use wasm_bindgen::prelude::*;
-
-use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen::from_value;
-
-#[derive(Serialize, Deserialize)]
-pub struct SchemaParams {
-    input: String,
-    enumHints: Vec<String>,
-    valuesHints: Vec<String>,
-    discriminatorHints: Vec<String>,
-    defaultNumberType: String,
-}
-
+use std::iter::FromIterator;
 
-#[wasm_bindgen]
-pub fn generate_schema(params_js: JsValue) -> Result<String, JsValue> {
-//     let params: SchemaParams = params_js.into_serde().map_err(|e| JsValue::from_str(&e.to_string()))?;
-    let params: SchemaParams = from_value(params_js).map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-//     let enum_hints: Vec<String> = serde_json::from_str(&enum_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
-//     let values_hints: Vec<String> = serde_json::from_str(&values_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
-//     let discriminator_hints: Vec<String> = serde_json::from_str(&discriminator_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-
-    let reader = BufReader::new(Cursor::new(params.input));
-
-    let enum_hints: Vec<Vec<_>> = params.enumHints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
-
-    let values_hints: Vec<Vec<_>> = params.valuesHints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
-
-    let discriminator_hints: Vec<Vec<_>> = params.discriminatorHints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
-
-    let default_num_type = match params.defaultNumberType.as_str() {
-        "int8" => NumType::Int8,
-        "uint8" => NumType::Uint8,
-        "int16" => NumType::Int16,
-        "uint16" => NumType::Uint16,
-        "int32" => NumType::Int32,
-        "uint32" => NumType::Uint32,
-        "float32" => NumType::Float32,
-        "float64" => NumType::Float64,
-        _ => return Err(JsValue::from_str("Invalid default number type")),
-    };
-
-
-    let hints = Hints::new(
-        default_num_type,
-        HintSet::new(enum_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(values_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(discriminator_hints.iter().map(|p| &p[..]).collect()),
-    );
-
-    let mut inferrer = Inferrer::new(hints);
-
-    let stream = Deserializer::from_reader(reader);
-    for value in stream.into_iter() {
-        inferrer = inferrer.infer(value.map_err(|e| JsValue::from_str(&e.to_string()))?);
-    }
-
-    let serde_schema: jtd::SerdeSchema = inferrer.into_schema().into_serde_schema();
-    serde_json::to_string(&serde_schema).map_err(|e| JsValue::from_str(&e.to_string()))
+/// Parses a JSON Pointer into a path, in the same form as the paths given to
+/// [`Hints`]. The reverse of [`to_json_pointer`].
+///
+/// This is lenient, for parsing pointers given by hand, such as on the command
+/// line: whatever comes before the first `/` is ignored, and so is a `~` that
+/// isn't part of an escape.
+///
+/// ```
+/// use jtd_infer::parse_json_pointer;
+///
+/// assert!(parse_json_pointer("").is_empty());
+/// assert_eq!(vec!["a/b", "~", "-"], parse_json_pointer("/a~1b/~0/-"));
+/// ```
+pub fn parse_json_pointer(s: &str) -> Vec<String> {
+    s.split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
 }
 
-
-fn parse_json_pointer(s: &str) -> Vec<String> {
-    if s == "" {
-        vec![]
-    } else {
-        s.replace("~1", "/")
-            .replace("!0", "~")
-            .split("/")
-            .skip(1)
-            .map(String::from)
-            .collect()
-    }
-}
-
-
 /// Keeps track of a sequence of example inputs, and can be converted into an
 /// inferred schema.
 pub struct Inferrer<'a> {
     inference: InferredSchema,
     hints: Hints<'a>,
+    samples: usize,
+    duplicate_keys: BTreeSet<Vec<String>>,
+    retained: Option<Vec<Value>>,
+    stats: Option<stats::Tracker>,
 }
 
 impl<'a> Inferrer<'a> {
@@ -168,25 +152,1240 @@ impl<'a> Inferrer<'a> {
         Self {
             inference: InferredSchema::Unknown,
             hints,
+            samples: 0,
+            duplicate_keys: BTreeSet::new(),
+            retained: None,
+            stats: None,
         }
     }
 
+    /// Carries on from `inference`, in place of whatever was inferred so far,
+    /// so that the examples given from now on are inferred on top of it.
+    ///
+    /// The examples that went into `inference` aren't known to the inferrer,
+    /// and so they aren't counted in [`Inferrer::num_samples`], retained, or
+    /// tracked in [`Inferrer::stats`]. `inference` should have been built up
+    /// with the same hints as this inferrer's. See [`Inference`].
+    pub fn with_inference(mut self, inference: Inference) -> Self {
+        self.inference = inference.0;
+        self
+    }
+
+    /// What's been inferred so far, without the rest of the inferrer, such as
+    /// to be carried on with by another one. See [`Inference`].
+    pub fn into_inference(self) -> Inference {
+        Inference(self.inference)
+    }
+
+    /// Keeps a copy of every example inferred from from now on, so that
+    /// [`Inferrer::into_validated_schema`] can check the schema against them.
+    ///
+    /// This costs as much memory as the examples themselves, so it's best
+    /// kept to tests and small inputs.
+    pub fn retaining_samples(mut self) -> Self {
+        self.retained.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Collects [`Stats`] about every example inferred from from now on, to be
+    /// returned by [`Inferrer::stats`].
+    ///
+    /// This costs a little time for every example, and memory for every part
+    /// of the input seen.
+    pub fn tracking_stats(mut self) -> Self {
+        self.stats.get_or_insert_with(Default::default);
+        self
+    }
+
     /// "Updates" the inference given an example data.
     ///
     /// Note that though the previous sentence uses the word "update", in Rust
     /// ownership terms this method *moves* `self`.
-    pub fn infer(self, value: Value) -> Self {
+    pub fn infer(mut self, value: Value) -> Self {
+        self.observe(&value);
+
         Self {
             inference: self.inference.infer(value, &self.hints),
             hints: self.hints,
+            samples: self.samples + 1,
+            duplicate_keys: self.duplicate_keys,
+            retained: self.retained,
+            stats: self.stats,
         }
     }
 
+    /// Records an example that's about to be inferred from, for those who
+    /// asked to retain samples or track stats.
+    fn observe(&mut self, value: &Value) {
+        if let Some(retained) = &mut self.retained {
+            retained.push(value.clone());
+        }
+
+        if let Some(stats) = &mut self.stats {
+            stats.observe(value, &self.hints, &mut Vec::new());
+        }
+    }
+
+    /// Like [`Inferrer::infer`], but also keeps track of the keys that the
+    /// example repeated, to report them in [`Inferrer::warnings`].
+    ///
+    /// As with [`Inferrer::infer`], only the last value of a repeated key is
+    /// inferred from.
+    pub fn infer_checked(mut self, checked: CheckedValue) -> Self {
+        self.duplicate_keys.extend(checked.duplicate_keys);
+        self.infer(checked.value)
+    }
+
+    /// Updates the inference with each element of `value`, if it's an array,
+    /// as a separate example. Anything else is a single example, just as with
+    /// [`Inferrer::infer`].
+    ///
+    /// Inferring from a whole array describes the array, as an `elements`
+    /// schema. This instead describes what's in it:
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Hints, Inferrer};
+    ///
+    /// let input = json!([{ "name": "Alice" }, { "name": "Bob" }]);
+    ///
+    /// assert_eq!(
+    ///     json!({ "elements": { "properties": { "name": { "type": "string" } } } }),
+    ///     Inferrer::new(Hints::default()).infer(input.clone()).into_json().unwrap(),
+    /// );
+    /// assert_eq!(
+    ///     json!({ "properties": { "name": { "type": "string" } } }),
+    ///     Inferrer::new(Hints::default()).infer_elements(input).into_json().unwrap(),
+    /// );
+    /// ```
+    pub fn infer_elements(mut self, value: Value) -> Self {
+        match value {
+            Value::Array(values) => {
+                self.extend(values);
+                self
+            }
+            value => self.infer(value),
+        }
+    }
+
+    /// Updates the inference with every JSON value in `reader`, in order.
+    ///
+    /// The values may be separated by whitespace, as in a file of
+    /// newline-delimited JSON. They're parsed one at a time, so the input is
+    /// never held in memory all at once. If any part of the input isn't valid
+    /// JSON, this returns [`InferError::InvalidJson`].
+    ///
+    /// If an object repeats a key, only the last value of it is inferred from.
+    /// To be warned about repeated keys, parse the input as [`CheckedValue`]s
+    /// and give them to [`Inferrer::infer_checked`] instead.
+    pub fn infer_json_stream<R: Read>(self, reader: R) -> Result<Self, InferError> {
+        let mut inferrer = self;
+        for value in Deserializer::from_reader(reader).into_iter() {
+            inferrer = inferrer.infer(value.map_err(|e| InferError::InvalidJson(e.to_string()))?);
+        }
+
+        Ok(inferrer)
+    }
+
+    /// Updates the inference with every example from an asynchronous stream,
+    /// in order.
+    ///
+    /// This is the same as calling [`Inferrer::infer`] on each element of
+    /// `stream` as it arrives. It's available with the `futures` feature.
+    #[cfg(feature = "futures")]
+    pub async fn infer_stream<S: futures::Stream<Item = Value>>(self, stream: S) -> Self {
+        use futures::StreamExt;
+
+        stream
+            .fold(self, |inferrer, value| async move { inferrer.infer(value) })
+            .await
+    }
+
+    /// Discards everything inferred so far, while keeping the same hints.
+    ///
+    /// This is useful when inferring schemas for many independent datasets
+    /// with the same configuration. An inferrer that was
+    /// [retaining samples][`Inferrer::retaining_samples`] or
+    /// [tracking stats][`Inferrer::tracking_stats`] keeps doing so.
+    pub fn reset(&mut self) {
+        self.inference = InferredSchema::Unknown;
+        self.samples = 0;
+        self.duplicate_keys.clear();
+        if let Some(retained) = &mut self.retained {
+            retained.clear();
+        }
+
+        if let Some(stats) = &mut self.stats {
+            stats.clear();
+        }
+    }
+
+    /// The number of examples given to [`Inferrer::infer`] so far.
+    pub fn num_samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Statistics about the examples seen so far, if the inferrer is
+    /// [tracking stats][`Inferrer::tracking_stats`].
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Hints, Inferrer};
+    ///
+    /// let stats = Inferrer::new(Hints::default())
+    ///     .tracking_stats()
+    ///     .infer(json!({ "age": 42 }))
+    ///     .infer(json!({ "age": null }))
+    ///     .stats()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "samples": 2,
+    ///         "positions": {
+    ///             "": { "count": 2, "nulls": 0 },
+    ///             "/age": { "count": 2, "nulls": 1, "min": 42.0, "max": 42.0 },
+    ///         },
+    ///         "enumFallbacks": [],
+    ///     }),
+    ///     serde_json::to_value(stats).unwrap(),
+    /// );
+    /// ```
+    pub fn stats(&self) -> Option<Stats> {
+        let stats = self.stats.as_ref()?;
+
+        let mut enum_fallbacks = Vec::new();
+        self.inference
+            .enum_fallbacks(&self.hints, &mut Vec::new(), &mut enum_fallbacks);
+        Some(stats.to_stats(self.samples, enum_fallbacks))
+    }
+
+    /// Describes anything questionable about the examples seen so far, such as
+    /// a part of the input that was sometimes a number and sometimes an
+    /// object.
+    ///
+    /// Warnings don't stop [`Inferrer::into_schema`] from producing a schema.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        self.inference
+            .warnings(&self.hints, &mut Vec::new(), &mut warnings);
+
+        let duplicate_keys = self.duplicate_keys.iter().cloned();
+        warnings.extend(duplicate_keys.map(|path| Warning::DuplicateKey { path }));
+        warnings
+    }
+
+    /// What has been inferred so far at the part of the input that `pointer`
+    /// points to, if anything has, without producing the whole schema.
+    ///
+    /// `pointer` is a JSON Pointer, with a `-` segment for the elements of an
+    /// array. This returns `None` if it isn't a valid JSON Pointer, or if it
+    /// points to where nothing has been seen.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Hints, InferredKind, Inferrer};
+    ///
+    /// let inferrer = Inferrer::new(Hints::default())
+    ///     .infer(json!({ "name": "Alice", "tags": [1, 2] }))
+    ///     .infer(json!({ "name": null, "tags": [] }));
+    ///
+    /// assert_eq!(Some(InferredKind::Object), inferrer.type_at(""));
+    /// assert_eq!(
+    ///     Some(InferredKind::Nullable(Box::new(InferredKind::String))),
+    ///     inferrer.type_at("/name"),
+    /// );
+    /// assert_eq!(Some(InferredKind::Number), inferrer.type_at("/tags/-"));
+    /// assert_eq!(None, inferrer.type_at("/age"));
+    /// ```
+    ///
+    /// This describes the inference itself, before some of the hints that
+    /// only apply when producing a schema, such as
+    /// [`Hints::with_values_collapse`] or [`Hints::with_emit_nullable`].
+    pub fn type_at(&self, pointer: &str) -> Option<InferredKind> {
+        let path = error::from_json_pointer(pointer)?;
+        self.inference.kind_at(&self.hints, &path)
+    }
+
     /// Converts the inference to a JSON Type Definition schema.
     ///
-    /// It is guaranteed that the resulting schema will accept all of the inputs
-    /// previously provided via [`Inferrer::infer`].
-    pub fn into_schema(self) -> Schema {
-        self.inference.into_schema(&self.hints)
+    /// With the default hints, the resulting schema accepts all of the inputs
+    /// previously provided via [`Inferrer::infer`]. Some hints give that up on
+    /// purpose: [`Hints::with_absent_as_nullable`], [`MixedScalars::String`],
+    /// [`NullableFallback::Base`], [`Hints::with_case_insensitive_keys`],
+    /// [`BigIntegers::String`], and enums of integers. See
+    /// [`Inferrer::into_validated_schema`] for what each one rejects, and to
+    /// check the inputs against the schema. To check whether it accepts any
+    /// other document, use [`schema_accepts`].
+    ///
+    /// Properties are only required if there were at least as many objects
+    /// where they could be as [`Hints::with_min_required_samples`] asks for.
+    ///
+    /// This fails if a type hint given in [`Hints::with_type_hints`] can't
+    /// accept the inputs found at its path.
+    ///
+    /// An inferrer that was never given any examples has nothing to go on, and
+    /// so converts to the empty form, `{}`, which accepts anything. A type
+    /// hint at the root still applies, and [`Hints::with_empty_fallback`] can
+    /// mark the empty form as unknown.
+    ///
+    /// The schema is checked with [`Schema::validate`] before it's returned,
+    /// so an invalid schema is reported as [`InferError::InvalidSchema`]
+    /// instead.
+    pub fn into_schema(self) -> Result<Schema, InferError> {
+        let schema = self.inference.into_schema(&self.hints)?;
+        schema.validate().map_err(InferError::InvalidSchema)?;
+
+        Ok(schema)
+    }
+
+    /// Like [`Inferrer::into_schema`], but also checks that the schema accepts
+    /// every example retained since [`Inferrer::retaining_samples`].
+    ///
+    /// The first example the schema rejects is reported as
+    /// [`InferError::SampleRejected`]. With the default hints, that should
+    /// never happen, and this is a safety net for bugs in this crate. Some
+    /// hints, though, infer schemas that reject some of the examples on
+    /// purpose, for consumers that change the data before validating it:
+    ///
+    /// - [`Hints::with_absent_as_nullable`] rejects objects that left out a
+    ///   property that's sometimes absent.
+    /// - [`MixedScalars::String`] rejects the scalars that aren't strings.
+    /// - [`NullableFallback::Base`] rejects nulls.
+    /// - [`Hints::with_case_insensitive_keys`] rejects every spelling of a key
+    ///   but the first.
+    /// - [`BigIntegers::String`] rejects the big integers themselves.
+    /// - Enums of integers, from [`Hints::with_integer_enums`] or an enum
+    ///   hint, reject the integers.
+    ///
+    /// With those, this finds out which examples, if any, the schema rejects.
+    /// If the inferrer wasn't retaining samples, there's nothing to check
+    /// against, and this is the same as [`Inferrer::into_schema`].
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Hints, Inferrer};
+    ///
+    /// let schema = Inferrer::new(Hints::default())
+    ///     .retaining_samples()
+    ///     .infer(json!({ "a": 1 }))
+    ///     .infer(json!({ "a": -1.5, "b": [true] }))
+    ///     .into_validated_schema();
+    ///
+    /// assert!(schema.is_ok());
+    /// ```
+    pub fn into_validated_schema(mut self) -> Result<Schema, InferError> {
+        let retained = self.retained.take().unwrap_or_default();
+        let schema = self.into_schema()?;
+
+        for (index, sample) in retained.iter().enumerate() {
+            if let Some((instance_path, schema_path)) = accepts::first_rejection(&schema, sample) {
+                return Err(InferError::SampleRejected {
+                    index,
+                    instance_path,
+                    schema_path,
+                });
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Like [`Inferrer::into_schema`], but gives the schema in its JSON form.
+    ///
+    /// This is the same as serializing the [`jtd::SerdeSchema`] that the
+    /// schema converts into.
+    pub fn into_json(self) -> Result<Value, InferError> {
+        let serde_schema = self.into_schema()?.into_serde_schema();
+        Ok(serde_json::to_value(serde_schema).expect("schemas always serialize to JSON"))
+    }
+
+    /// Like [`Inferrer::into_schema`], but also records in the schema's
+    /// top-level `metadata` how it was generated.
+    ///
+    /// The record is stored under `jtdInfer`, as an object with the `version`
+    /// of this crate, the number of `samples` inferred from, and the time it
+    /// was `generatedAt`. Since JSON Typedef metadata is freeform, the schema
+    /// is otherwise unaffected.
+    pub fn into_stamped_schema(self) -> Result<Schema, InferError> {
+        let samples = self.samples;
+        let mut schema = self.into_schema()?;
+        stamp::stamp(&mut schema, samples, chrono::Utc::now());
+
+        Ok(schema)
+    }
+}
+
+/// Infers from every example in an iterator, with [`Hints::default`]. That
+/// means no hints, and a default number type of [`NumType::Uint8`], so that
+/// numbers are given the smallest type that fits them:
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::Inferrer;
+///
+/// let inferrer: Inferrer = vec![json!(1), json!(2)].into_iter().collect();
+/// assert_eq!(2, inferrer.num_samples());
+/// assert_eq!(json!({ "type": "uint8" }), inferrer.into_json().unwrap());
+/// ```
+///
+/// To use other hints, construct the inferrer with [`Inferrer::new`] and then
+/// [`Extend::extend`] it.
+impl FromIterator<Value> for Inferrer<'_> {
+    fn from_iter<I: IntoIterator<Item = Value>>(values: I) -> Self {
+        let mut inferrer = Inferrer::new(Hints::default());
+        inferrer.extend(values);
+        inferrer
+    }
+}
+
+/// Updates the inference with every example from an iterator, in order, just
+/// like calling [`Inferrer::infer`] on each of them.
+impl Extend<Value> for Inferrer<'_> {
+    fn extend<I: IntoIterator<Item = Value>>(&mut self, values: I) {
+        for value in values {
+            self.observe(&value);
+
+            let inference = std::mem::replace(&mut self.inference, InferredSchema::Unknown);
+            self.inference = inference.infer(value, &self.hints);
+            self.samples += 1;
+        }
+    }
+}
+
+/// Updates the inference with every checked example from an iterator, in
+/// order, just like calling [`Inferrer::infer_checked`] on each of them.
+impl Extend<CheckedValue> for Inferrer<'_> {
+    fn extend<I: IntoIterator<Item = CheckedValue>>(&mut self, values: I) {
+        for checked in values {
+            self.duplicate_keys.extend(checked.duplicate_keys);
+            self.extend(std::iter::once(checked.value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reset() {
+        let mut inferrer = Inferrer::new(Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        ));
+
+        inferrer = inferrer.infer(json!({ "a": 1, "b": "x" }));
+        inferrer = inferrer.infer(json!({ "a": 2 }));
+
+        assert_eq!(2, inferrer.num_samples());
+
+        inferrer.reset();
+        inferrer = inferrer.infer(json!({ "c": true }));
+        assert_eq!(1, inferrer.num_samples());
+
+        assert_eq!(
+            json!({ "properties": { "c": { "type": "boolean" } } }),
+            inferrer.into_json().unwrap()
+        );
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn infer_stream() {
+        let stream = futures::stream::iter(vec![json!({ "a": 1 }), json!({ "a": 2, "b": true })]);
+        let inferrer = Inferrer::new(Hints::default()).infer_stream(stream);
+        let inferrer = futures::executor::block_on(inferrer);
+
+        assert_eq!(2, inferrer.num_samples());
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "uint8" } },
+                "optionalProperties": { "b": { "type": "boolean" } },
+            }),
+            inferrer.into_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn infer_json_stream() {
+        let input = "{ \"a\": 1 }\n{ \"a\": 2, \"b\": true }\n";
+        let inferrer = Inferrer::new(Hints::default())
+            .infer_json_stream(input.as_bytes())
+            .unwrap();
+
+        assert_eq!(2, inferrer.num_samples());
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "uint8" } },
+                "optionalProperties": { "b": { "type": "boolean" } },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        let err = Inferrer::new(Hints::default())
+            .infer_json_stream("{ \"a\": 1 } { \"a\": ".as_bytes())
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            "invalid JSON input: EOF while parsing a value at line 1 column 18",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let values = vec![json!({ "a": 1 }), json!({ "a": 2, "b": true })];
+
+        let collected: Inferrer = values.clone().into_iter().collect();
+        let inferred = values
+            .clone()
+            .into_iter()
+            .fold(Inferrer::new(Hints::default()), Inferrer::infer);
+
+        assert_eq!(inferred.num_samples(), collected.num_samples());
+        assert_eq!(
+            inferred.into_schema().unwrap(),
+            collected.into_schema().unwrap()
+        );
+
+        let mut extended = Inferrer::new(Hints::default()).infer(json!({ "a": 3 }));
+        extended.extend(values);
+
+        assert_eq!(3, extended.num_samples());
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "uint8" } },
+                "optionalProperties": { "b": { "type": "boolean" } },
+            }),
+            extended.into_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let input =
+            r#"{ "a": 1, "a": "x" } { "a": "y", "b": [{ "c": 1, "c": 2 }, { "c": 3, "c": 4 }] }"#;
+        let mut inferrer = Inferrer::new(Hints::default());
+        for checked in Deserializer::from_str(input).into_iter::<CheckedValue>() {
+            inferrer = inferrer.infer_checked(checked.unwrap());
+        }
+
+        let a = vec!["a".to_owned()];
+        let c = vec!["b".to_owned(), "-".to_owned(), "c".to_owned()];
+        assert_eq!(
+            vec![
+                Warning::DuplicateKey { path: a },
+                Warning::DuplicateKey { path: c },
+            ],
+            inferrer.warnings()
+        );
+
+        // Only the last value of a repeated key is inferred from, so the 1 in
+        // the first example doesn't conflict with the strings.
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "string" } },
+                "optionalProperties": {
+                    "b": { "elements": { "properties": { "c": { "type": "uint8" } } } },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn min_required_samples() {
+        let values = vec![
+            json!({ "a": 1, "b": { "c": true } }),
+            json!({ "a": 2, "b": { "c": false } }),
+        ];
+
+        let mut inferrer = Inferrer::new(Hints::default().with_min_required_samples(3));
+        inferrer.extend(values.clone());
+
+        assert_eq!(
+            json!({
+                "optionalProperties": {
+                    "a": { "type": "uint8" },
+                    "b": { "optionalProperties": { "c": { "type": "boolean" } } },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        let mut inferrer = Inferrer::new(Hints::default().with_min_required_samples(2));
+        inferrer.extend(values);
+
+        assert_eq!(
+            json!({
+                "properties": {
+                    "a": { "type": "uint8" },
+                    "b": { "properties": { "c": { "type": "boolean" } } },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn optional_nested_object() {
+        // "b" is only in some examples, but whenever it is, so are "c" and
+        // "d". Those are required within "b", however many examples left it
+        // out.
+        let values = vec![
+            json!({ "a": 1 }),
+            json!({ "a": 2, "b": { "c": true, "d": "x" } }),
+            json!({ "a": 3, "b": null }),
+            json!({ "a": 4 }),
+            json!({ "a": 5, "b": { "c": false, "d": "y" } }),
+            json!({ "a": 6, "e": [{ "f": 1 }, { "f": 2 }] }),
+        ];
+
+        let mut inferrer = Inferrer::new(Hints::default());
+        inferrer.extend(values.clone());
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "uint8" } },
+                "optionalProperties": {
+                    "b": {
+                        "nullable": true,
+                        "properties": {
+                            "c": { "type": "boolean" },
+                            "d": { "type": "string" },
+                        },
+                    },
+                    "e": {
+                        "elements": { "properties": { "f": { "type": "uint8" } } },
+                    },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        // With a minimum, the objects in "b" are counted on their own, as are
+        // the elements of "e". There were six examples, but only two of each.
+        let mut inferrer = Inferrer::new(Hints::default().with_min_required_samples(3));
+        inferrer.extend(values.clone());
+        assert_eq!(
+            json!({
+                "properties": { "a": { "type": "uint8" } },
+                "optionalProperties": {
+                    "b": {
+                        "nullable": true,
+                        "optionalProperties": {
+                            "c": { "type": "boolean" },
+                            "d": { "type": "string" },
+                        },
+                    },
+                    "e": {
+                        "elements": { "optionalProperties": { "f": { "type": "uint8" } } },
+                    },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        let mut inferrer = Inferrer::new(Hints::default().with_min_required_samples(2));
+        inferrer.extend(values);
+        let schema = inferrer.into_json().unwrap();
+        assert_eq!(
+            json!(["c", "d"]),
+            json!(schema["optionalProperties"]["b"]["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>())
+        );
+    }
+
+    #[test]
+    fn into_validated_schema() {
+        let inputs = vec![
+            json!({ "a": 1, "b": "2020-01-01T00:00:00Z" }),
+            json!({ "a": -300.5, "b": null, "c": [{ "d": true }] }),
+            json!({ "a": 7 }),
+        ];
+
+        let mut inferrer = Inferrer::new(Hints::default()).retaining_samples();
+        inferrer.extend(inputs.clone());
+        assert_eq!(inputs, *inferrer.retained.as_ref().unwrap());
+        assert!(inferrer.into_validated_schema().is_ok());
+
+        // A sample that wasn't inferred from stands in for an inference bug.
+        let mut inferrer = Inferrer::new(Hints::default())
+            .retaining_samples()
+            .infer(json!({ "a": 1 }))
+            .infer(json!({ "a": 2 }));
+        inferrer.retained.as_mut().unwrap()[1] = json!({ "a": "x" });
+        assert_eq!(
+            Err(InferError::SampleRejected {
+                index: 1,
+                instance_path: vec!["a".to_owned()],
+                schema_path: vec!["properties".to_owned(), "a".to_owned(), "type".to_owned()],
+            }),
+            inferrer.into_validated_schema()
+        );
+
+        let mut inferrer = Inferrer::new(Hints::default())
+            .retaining_samples()
+            .infer(json!(1));
+        inferrer.reset();
+        assert_eq!(Some(vec![]), inferrer.retained);
+
+        // Without retaining samples, there's nothing to check.
+        assert!(Inferrer::new(Hints::default())
+            .infer(json!(1))
+            .into_validated_schema()
+            .is_ok());
+    }
+
+    /// The index of the first of `examples` that the schema inferred from them
+    /// rejects, as reported by [`Inferrer::into_validated_schema`].
+    fn first_rejected(hints: Hints, examples: &[Value]) -> Option<usize> {
+        let inferrer = examples
+            .iter()
+            .cloned()
+            .fold(Inferrer::new(hints).retaining_samples(), Inferrer::infer);
+
+        match inferrer.into_validated_schema() {
+            Ok(_) => None,
+            Err(InferError::SampleRejected { index, .. }) => Some(index),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn rejected_with_absent_as_nullable() {
+        let examples = [json!({ "a": 1 }), json!({})];
+        assert_eq!(None, first_rejected(Hints::default(), &examples));
+        assert_eq!(
+            Some(1),
+            first_rejected(Hints::default().with_absent_as_nullable(true), &examples)
+        );
+    }
+
+    #[test]
+    fn rejected_with_mixed_scalars() {
+        let examples = [json!({ "a": 1 }), json!({ "a": "x" })];
+        assert_eq!(None, first_rejected(Hints::default(), &examples));
+        assert_eq!(
+            Some(0),
+            first_rejected(
+                Hints::default().with_mixed_scalars(MixedScalars::String),
+                &examples
+            )
+        );
+    }
+
+    #[test]
+    fn rejected_with_nullable_fallback() {
+        let examples = [json!({ "a": 1 }), json!({ "a": null })];
+        let hints = || Hints::default().with_emit_nullable(false);
+        assert_eq!(None, first_rejected(hints(), &examples));
+        assert_eq!(
+            Some(1),
+            first_rejected(
+                hints().with_nullable_fallback(NullableFallback::Base),
+                &examples
+            )
+        );
+    }
+
+    #[test]
+    fn rejected_with_case_insensitive_keys() {
+        let examples = [json!({ "userId": 1 }), json!({ "UserID": 2 })];
+        assert_eq!(None, first_rejected(Hints::default(), &examples));
+        assert_eq!(
+            Some(1),
+            first_rejected(Hints::default().with_case_insensitive_keys(true), &examples)
+        );
+    }
+
+    #[test]
+    fn rejected_with_big_integers() {
+        let big: Value = serde_json::from_str("123456789012345678901234567890").unwrap();
+        let examples = [json!({ "id": big })];
+        assert_eq!(None, first_rejected(Hints::default(), &examples));
+        assert_eq!(
+            Some(0),
+            first_rejected(
+                Hints::default().with_big_integers(BigIntegers::String),
+                &examples
+            )
+        );
+    }
+
+    #[test]
+    fn rejected_with_integer_enums() {
+        let examples = [json!({ "code": 1 }), json!({ "code": 1 })];
+        assert_eq!(None, first_rejected(Hints::default(), &examples));
+        assert_eq!(
+            Some(0),
+            first_rejected(Hints::default().with_integer_enums(5), &examples)
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let enum_path = vec!["e".to_string()];
+        let values_path = vec!["m".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![&enum_path]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![]),
+        )
+        .with_max_enum_size(1);
+
+        let mut inferrer = Inferrer::new(hints)
+            .tracking_stats()
+            .infer(json!({ "e": "a", "m": { "x": 1, "y": -2.5 }, "l": [null, 3] }))
+            .infer(json!({ "e": "b", "m": { "z": null }, "l": [] }));
+
+        assert_eq!(
+            json!({
+                "samples": 2,
+                "positions": {
+                    "": { "count": 2, "nulls": 0 },
+                    "/e": { "count": 2, "nulls": 0 },
+                    "/l": { "count": 2, "nulls": 0 },
+                    "/l/-": { "count": 2, "nulls": 1, "min": 3.0, "max": 3.0 },
+                    "/m": { "count": 2, "nulls": 0 },
+                    "/m/-": { "count": 3, "nulls": 1, "min": -2.5, "max": 1.0 },
+                },
+                "enumFallbacks": ["/e"],
+            }),
+            serde_json::to_value(inferrer.stats().unwrap()).unwrap()
+        );
+
+        // Hints reach the parts of the input that they'd apply to, the same
+        // way as when inferring. The values of "m" are only ever "-".
+        let stats = inferrer.stats().unwrap();
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+        assert!(stats.reaches(&[]));
+        assert!(stats.reaches(&path(&["m", "-"])));
+        assert!(stats.reaches(&path(&["-", "-"])));
+        assert!(!stats.reaches(&path(&["m", "x"])));
+        assert!(!stats.reaches(&path(&["e", "-"])));
+        assert!(!stats.reaches(&path(&["missing"])));
+
+        inferrer.reset();
+        let stats = inferrer.stats().unwrap();
+        assert_eq!(0, stats.samples);
+        assert!(stats.positions.is_empty());
+
+        // Without tracking stats, there are none to report.
+        assert_eq!(
+            None,
+            Inferrer::new(Hints::default()).infer(json!(1)).stats()
+        );
+    }
+
+    #[test]
+    fn root_enum() {
+        let root = vec![];
+        let hints = || {
+            Hints::new(
+                NumType::Uint8,
+                HintSet::new(vec![&root]),
+                HintSet::new(vec![]),
+                HintSet::new(vec![]),
+            )
+        };
+
+        let inputs = vec![json!("active"), json!("inactive"), json!("active")];
+        let inferrer: Inferrer = inputs
+            .iter()
+            .cloned()
+            .fold(Inferrer::new(hints()), Inferrer::infer);
+        assert_eq!(Some(InferredKind::Enum), inferrer.type_at(""));
+
+        let schema = inferrer.into_schema().unwrap();
+        assert!(inputs.iter().all(|input| schema_accepts(&schema, input)));
+        assert!(!schema_accepts(&schema, &json!("deleted")));
+        assert_eq!(
+            json!({ "enum": ["active", "inactive"] }),
+            serde_json::to_value(schema.into_serde_schema()).unwrap()
+        );
+
+        // Past the most values an enum may have, the root is a plain string.
+        let inferrer = inputs.into_iter().fold(
+            Inferrer::new(hints().with_max_enum_size(1)),
+            Inferrer::infer,
+        );
+        assert_eq!(json!({ "type": "string" }), inferrer.into_json().unwrap());
+    }
+
+    #[test]
+    fn type_at() {
+        use InferredKind::*;
+
+        let discriminator_path = vec!["events".to_string(), "-".to_string(), "type".to_string()];
+        let values_path = vec!["counts".to_string()];
+        let type_path = vec!["id".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![&values_path]),
+            HintSet::new(vec![&discriminator_path]),
+        )
+        .with_integer_enums(5)
+        .with_type_hints(HintMap::new(vec![(&type_path, jtd::Type::String)]));
+
+        let inferrer = Inferrer::new(hints)
+            .infer(json!({
+                "id": "x",
+                "counts": { "a": 1 },
+                "events": [{ "type": "a", "x": 1, "y": true }, { "type": "b", "x": "s" }],
+                "a/b": { "~c": 1 },
+                "small": 1,
+            }))
+            .infer(json!({ "id": null, "counts": { "b": 2.5 }, "small": 1 }));
+
+        assert_eq!(Some(Object), inferrer.type_at(""));
+        assert_eq!(Some(Nullable(Box::new(String))), inferrer.type_at("/id"));
+        assert_eq!(Some(Values), inferrer.type_at("/counts"));
+        assert_eq!(Some(Number), inferrer.type_at("/counts/-"));
+        assert_eq!(Some(Number), inferrer.type_at("/counts/anything"));
+        assert_eq!(Some(Discriminator), inferrer.type_at("/events/-"));
+        assert_eq!(Some(String), inferrer.type_at("/events/-/type"));
+        assert_eq!(Some(Boolean), inferrer.type_at("/events/-/y"));
+        assert_eq!(Some(Any), inferrer.type_at("/events/-/x"));
+        assert_eq!(Some(Number), inferrer.type_at("/a~1b/~0c"));
+        assert_eq!(Some(Enum), inferrer.type_at("/small"));
+
+        // Parts of the input that weren't seen, and pointers that aren't
+        // valid.
+        assert_eq!(None, inferrer.type_at("/events/-/z"));
+        assert_eq!(None, inferrer.type_at("/events/0"));
+        assert_eq!(None, inferrer.type_at("/id/x"));
+        assert_eq!(None, inferrer.type_at("id"));
+        assert_eq!(None, inferrer.type_at("/a~2b"));
+
+        assert_eq!(Some(Unknown), Inferrer::new(Hints::default()).type_at(""));
+        assert_eq!(
+            Some(Nullable(Box::new(Unknown))),
+            Inferrer::new(Hints::default())
+                .infer(json!(null))
+                .type_at("")
+        );
+    }
+
+    #[test]
+    fn infer_elements() {
+        let input = json!([{ "id": 1, "tags": ["a"] }, { "id": 2, "note": null }]);
+
+        // The array as a whole, described as one example.
+        let inferrer = Inferrer::new(Hints::default()).infer(input.clone());
+        assert_eq!(1, inferrer.num_samples());
+        assert_eq!(
+            json!({
+                "elements": {
+                    "properties": { "id": { "type": "uint8" } },
+                    "optionalProperties": {
+                        "note": {},
+                        "tags": { "elements": { "type": "string" } },
+                    },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        // Each of its elements, described as separate examples.
+        let inferrer = Inferrer::new(Hints::default()).infer_elements(input);
+        assert_eq!(2, inferrer.num_samples());
+        assert_eq!(
+            json!({
+                "properties": { "id": { "type": "uint8" } },
+                "optionalProperties": {
+                    "note": {},
+                    "tags": { "elements": { "type": "string" } },
+                },
+            }),
+            inferrer.into_json().unwrap()
+        );
+
+        // Anything but an array is a single example either way.
+        let inferrer = Inferrer::new(Hints::default()).infer_elements(json!({ "id": 1 }));
+        assert_eq!(1, inferrer.num_samples());
+        assert_eq!(
+            json!({ "properties": { "id": { "type": "uint8" } } }),
+            inferrer.into_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn absent_as_nullable_acceptance() {
+        let inputs = [
+            json!({ "name": "Joe", "age": 42 }),
+            json!({ "name": "Jane" }),
+        ];
+        let infer = |hints: Hints| {
+            inputs
+                .iter()
+                .cloned()
+                .fold(Inferrer::new(hints), Inferrer::infer)
+                .into_schema()
+                .unwrap()
+        };
+
+        let optional = infer(Hints::default());
+        let nullable = infer(Hints::default().with_absent_as_nullable(true));
+
+        // An absent property is only accepted when it's optional, and a null
+        // one only when it's nullable.
+        let absent = json!({ "name": "Jane" });
+        let null = json!({ "name": "Jane", "age": null });
+        assert!(schema_accepts(&optional, &absent));
+        assert!(!schema_accepts(&optional, &null));
+        assert!(!schema_accepts(&nullable, &absent));
+        assert!(schema_accepts(&nullable, &null));
+
+        // Both accept a property that's there.
+        assert!(schema_accepts(&optional, &inputs[0]));
+        assert!(schema_accepts(&nullable, &inputs[0]));
+    }
+
+    #[test]
+    fn discriminator_order() {
+        let discriminator_path = vec!["type".to_string()];
+        let inputs = vec![
+            json!({ "type": "s", "value": "foo" }),
+            json!({ "type": "n", "value": 2.5 }),
+            json!({ "type": "b", "value": true, "extra": null }),
+            json!({ "type": "s", "value": "bar", "extra": 1 }),
+            json!({ "type": "a" }),
+        ];
+
+        // Every order of the same examples gives the same schema, with its
+        // mapping keys in sorted order.
+        let outputs: Vec<_> = (0..inputs.len())
+            .flat_map(|n| {
+                let mut rotated = inputs.clone();
+                rotated.rotate_left(n);
+                let mut reversed = rotated.clone();
+                reversed.reverse();
+                vec![rotated, reversed]
+            })
+            .map(|inputs| {
+                let hints = Hints::new(
+                    NumType::Uint8,
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![&discriminator_path]),
+                );
+                let schema = inputs
+                    .into_iter()
+                    .fold(Inferrer::new(hints), Inferrer::infer)
+                    .into_schema()
+                    .unwrap();
+
+                (
+                    serde_json::to_string(&schema.clone().into_serde_schema()).unwrap(),
+                    serde_json::to_string(&to_json_schema(&schema)).unwrap(),
+                    to_rust(&schema),
+                )
+            })
+            .collect();
+
+        assert!(outputs.iter().all(|output| output == &outputs[0]));
+
+        let jtd: Value = serde_json::from_str(&outputs[0].0).unwrap();
+        let keys: Vec<_> = jtd["mapping"].as_object().unwrap().keys().collect();
+        assert_eq!(vec!["a", "b", "n", "s"], keys);
+        assert!(outputs[0].0.find("\"a\"") < outputs[0].0.find("\"s\""));
+    }
+
+    #[test]
+    fn discriminator_edge_cases() {
+        let discriminator_path = vec!["-".to_string(), "type".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![&discriminator_path]),
+        );
+
+        let inputs = vec![
+            // A nullable discriminator, and a mapping with nothing but a tag.
+            json!([{ "type": "a", "x": 1 }, null, { "type": "b" }]),
+            // A property of a mapping's object that has the same name as the
+            // tag, further down.
+            json!([{ "type": "a", "x": { "type": 5 } }]),
+            // The same mapping key seen with different sets of properties.
+            json!([{ "type": "a", "x": 1 }, { "type": "a", "y": "z" }]),
+        ];
+
+        for input in inputs {
+            let schema = Inferrer::new(Hints::new(
+                NumType::Uint8,
+                HintSet::new(vec![]),
+                HintSet::new(vec![]),
+                HintSet::new(vec![&discriminator_path]),
+            ))
+            .infer(input.clone())
+            .into_schema()
+            .unwrap();
+
+            assert!(schema.validate().is_ok(), "{}", input);
+            assert!(
+                jtd::validate(&schema, &input, Default::default())
+                    .unwrap()
+                    .is_empty(),
+                "{}",
+                input
+            );
+        }
+
+        // Every object has to have the tag, as a string, wherever it comes.
+        let inputs = vec![
+            (json!([{ "type": "a" }, { "type": 1 }]), 1),
+            (json!([{ "type": "a" }, { "x": 1 }]), 1),
+            (json!([{ "x": 1 }, { "type": "a" }, { "type": null }]), 2),
+            (json!([{ "type": 1 }]), 1),
+        ];
+
+        for (input, untagged) in inputs {
+            assert_eq!(
+                InferError::DiscriminatorTagMissing {
+                    path: vec!["-".to_owned()],
+                    discriminator: "type".to_owned(),
+                    untagged,
+                },
+                Inferrer::new(Hints::new(
+                    NumType::Uint8,
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![]),
+                    HintSet::new(vec![&discriminator_path]),
+                ))
+                .infer(input.clone())
+                .into_schema()
+                .unwrap_err(),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(
+            r#"discriminator hint at "/-/type" needs every object at "/-" to have a string "type" property, but 2 objects didn't"#,
+            Inferrer::new(hints)
+                .infer(json!([{ "type": "a" }, {}, { "type": ["b"] }]))
+                .into_schema()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn inference() {
+        let hints = Hints::default();
+        let inputs = [json!({ "a": 1 }), json!({ "a": "x", "b": null })];
+
+        // Inferring piecemeal, or across inferrers, gives the same schema as
+        // one inferrer given every input.
+        let expected = Inferrer::new(Hints::default())
+            .infer(inputs[0].clone())
+            .infer(inputs[1].clone())
+            .into_schema()
+            .unwrap();
+
+        let piecemeal = Inference::from_value(&inputs[0], &hints)
+            .infer(inputs[1].clone(), &hints)
+            .into_schema(&hints)
+            .unwrap();
+        assert_eq!(expected, piecemeal);
+
+        let handed_over = Inferrer::new(Hints::default())
+            .infer(inputs[0].clone())
+            .into_inference();
+        let inferrer = Inferrer::new(Hints::default())
+            .with_inference(handed_over)
+            .infer(inputs[1].clone());
+        assert_eq!(1, inferrer.num_samples());
+        assert_eq!(expected, inferrer.into_schema().unwrap());
+
+        assert_eq!(InferredKind::Unknown, Inference::unknown().kind(&hints));
+        assert_eq!(
+            json!({}),
+            serde_json::to_value(
+                Inference::default()
+                    .into_schema(&hints)
+                    .unwrap()
+                    .into_serde_schema()
+            )
+            .unwrap()
+        );
+
+        let mixed = Inference::from_value(&inputs[0], &hints).infer(json!("x"), &hints);
+        assert_eq!(InferredKind::Any, mixed.kind(&hints));
+        assert_eq!(
+            Inferrer::new(Hints::default())
+                .infer(inputs[0].clone())
+                .infer(json!("x"))
+                .warnings(),
+            mixed.warnings(&hints)
+        );
+    }
+
+    #[test]
+    fn no_examples() {
+        let root = vec![];
+        let root_hints = || {
+            Hints::new(
+                NumType::Uint8,
+                HintSet::new(vec![&root]),
+                HintSet::new(vec![&root]),
+                HintSet::new(vec![]),
+            )
+        };
+
+        for inferrer in [
+            Inferrer::new(Hints::default()),
+            Inferrer::new(root_hints()),
+            Inferrer::new(Hints::default()).retaining_samples(),
+            Inferrer::new(Hints::default()).tracking_stats(),
+        ] {
+            assert_eq!(json!({}), inferrer.into_json().unwrap());
+        }
+
+        assert_eq!(
+            json!({}),
+            serde_json::to_value(
+                Inferrer::new(Hints::default())
+                    .retaining_samples()
+                    .into_validated_schema()
+                    .unwrap()
+                    .into_serde_schema()
+            )
+            .unwrap()
+        );
+
+        // Hints that only say what to do with data still apply at the root.
+        let type_hints = vec![(root.clone(), jtd::Type::String)];
+        assert_eq!(
+            json!({ "type": "string" }),
+            Inferrer::new(Hints::default().with_type_hints(HintMap::from_paths(&type_hints)))
+                .into_json()
+                .unwrap()
+        );
+        assert_eq!(
+            json!({ "metadata": { "unknown": true } }),
+            Inferrer::new(Hints::default().with_empty_fallback(EmptyFallback::Unknown))
+                .into_json()
+                .unwrap()
+        );
+
+        let stamped = Inferrer::new(Hints::default())
+            .into_stamped_schema()
+            .unwrap();
+        assert_eq!(json!(0), stamped.metadata()["jtdInfer"]["samples"]);
+        let stats = Inferrer::new(Hints::default()).tracking_stats().stats();
+        assert_eq!(0, stats.unwrap().samples);
     }
 }
@@ -1,78 +1,1683 @@
-use anyhow::Error;
+use anyhow::{bail, Context, Error};
 use clap::{crate_version, load_yaml, App, AppSettings};
-use jtd_infer::{HintSet, Hints, Inferrer, NumType};
-use serde_json::Deserializer;
+use jtd::{Schema, Type};
+use jtd_infer::{
+    name_root, parse_json_pointer, to_json_pointer, to_json_schema, to_rust, BigIntegers,
+    BooleanStrings, CheckedValue, Config, DateDetection, EmptyFallback, Inferrer, MixedScalars,
+    NullableFallback, NumType, Stats,
+};
+use log::{debug, info, warn, LevelFilter, Log, Metadata, Record};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::de::DeserializeOwned;
+use serde_json::{Deserializer, Map, Value};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::stdin;
 use std::io::BufReader;
 use std::io::Read;
+use std::path::Path;
 
-use wasm_bindgen::prelude::*;
+fn main() -> Result<(), Error> {
+    let cli_yaml = load_yaml!("cli.yaml");
+    let matches = App::from(cli_yaml)
+        .setting(AppSettings::ColoredHelp)
+        .version(crate_version!())
+        .get_matches();
 
-#[wasm_bindgen]
-pub fn greet(name: &str) -> String {
-    format!("Hello, {}!", name)
-}
+    // Diagnostics go to stderr, one level more detailed for each -v.
+    let level = match (
+        matches.is_present("quiet"),
+        matches.occurrences_of("verbose"),
+    ) {
+        (true, _) => LevelFilter::Off,
+        (false, 0) => LevelFilter::Warn,
+        (false, 1) => LevelFilter::Info,
+        (false, _) => LevelFilter::Debug,
+    };
+    log::set_logger(&StderrLogger).expect("the logger is only set once");
+    log::set_max_level(level);
 
-#[wasm_bindgen]
-pub fn generate_schema(
-    input: &str,
-    enum_hints: Vec<String>,
-    values_hints: Vec<String>,
-    discriminator_hints: Vec<String>,
-    default_number_type: &str,
-) -> Result<String, JsValue> {
-    // Parse the inputs. This replaces what `clap` did in the CLI.
+    let mut config = match matches.value_of("config") {
+        Some(path) => read_config(path)?,
+        None => Config::default(),
+    };
 
-    let reader = BufReader::new(Cursor::new(input));
+    // Hints given as flags are added to the ones in the config file, and other
+    // flags override it.
+    let pointers = |name| {
+        matches
+            .values_of(name)
+            .unwrap_or_default()
+            .map(parse_json_pointer)
+    };
 
-    let enum_hints: Vec<Vec<_>> = enum_hints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
+    config.enum_hints.extend(pointers("enum-hint"));
+    config.values_hints.extend(pointers("values-hint"));
+    config
+        .discriminator_hints
+        .extend(pointers("discriminator-hint"));
+    config
+        .open_object_hints
+        .extend(pointers("open-object-hint"));
+    config.any_hints.extend(pointers("any-hint"));
 
-    let values_hints: Vec<Vec<_>> = values_hints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
+    for type_hint in matches.values_of("type-hint").unwrap_or_default() {
+        config.type_hints.push(parse_type_hint(type_hint)?);
+    }
 
-    let discriminator_hints: Vec<Vec<_>> = discriminator_hints
-        .iter()
-        .map(|hint| parse_json_pointer(hint))
-        .collect();
+    for number_type_hint in matches.values_of("number-type-hint").unwrap_or_default() {
+        config.number_type_hints.push(parse_number_type_hint(
+            "number-type-hint",
+            number_type_hint,
+        )?);
+    }
+
+    for number_type_hint in matches
+        .values_of("subtree-number-type-hint")
+        .unwrap_or_default()
+    {
+        config
+            .subtree_number_type_hints
+            .push(parse_number_type_hint(
+                "subtree-number-type-hint",
+                number_type_hint,
+            )?);
+    }
+
+    if matches.occurrences_of("default-number-type") > 0 {
+        let name = matches.value_of("default-number-type").unwrap();
+        config.default_number_type = name.parse()?;
+    }
+
+    match matches.value_of("detect-dates") {
+        Some("lenient") => config.date_detection = DateDetection::Lenient,
+        Some("strict") => config.date_detection = DateDetection::Strict,
+        Some(_) => unreachable!(),
+        None => {}
+    }
 
-    let default_num_type = match default_number_type {
-        // ... match arms similar to your main.rs
+    if let Some(tokens) = matches.values_of("boolean-string") {
+        config.boolean_strings = Some(BooleanStrings::new(tokens.map(String::from).collect()));
+    } else if matches.is_present("boolean-strings") && config.boolean_strings.is_none() {
+        config.boolean_strings = Some(BooleanStrings::default());
+    }
+
+    if matches.is_present("boolean-strings-ignore-case") {
+        let boolean_strings = config.boolean_strings.take().unwrap_or_default();
+        config.boolean_strings = Some(boolean_strings.with_ignore_case(true));
+    }
+
+    if matches.occurrences_of("mixed-scalars") > 0 {
+        config.mixed_scalars = match matches.value_of("mixed-scalars").unwrap() {
+            "empty" => MixedScalars::Empty,
+            "string" => MixedScalars::String,
+            _ => unreachable!(),
+        };
+    }
+
+    // Each switch has an opposite, such as --no-open-objects, to turn off what
+    // a config file turns on. Whichever of the two comes last wins.
+    let switch = |on: &str, off: &str| {
+        if matches.is_present(on) {
+            Some(true)
+        } else if matches.is_present(off) {
+            Some(false)
+        } else {
+            None
+        }
     };
 
-    let hints = Hints::new(
-        default_num_type,
-        HintSet::new(enum_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(values_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(discriminator_hints.iter().map(|p| &p[..]).collect()),
-    );
+    let switches = [
+        ("open-objects", &mut config.open_objects),
+        ("histograms", &mut config.histograms),
+        ("tuples", &mut config.tuples),
+        ("key-descriptions", &mut config.key_descriptions),
+        ("case-insensitive-keys", &mut config.case_insensitive_keys),
+        ("absent-as-nullable", &mut config.absent_as_nullable),
+        ("unknown-as-number", &mut config.unknown_as_number),
+        ("nested-values", &mut config.nested_values),
+        ("detect-emails", &mut config.email_detection),
+    ];
+
+    for (name, option) in switches {
+        if let Some(on) = switch(name, &format!("no-{}", name)) {
+            *option = on;
+        }
+    }
+
+    if let Some(emit_nullable) = switch("emit-nullable", "no-nullable") {
+        config.emit_nullable = emit_nullable;
+    }
+
+    match matches.value_of("empty-fallback") {
+        Some("empty") => config.empty_fallback = EmptyFallback::Empty,
+        Some("unknown") => config.empty_fallback = EmptyFallback::Unknown,
+        Some("number") => config.empty_fallback = EmptyFallback::Number,
+        Some(_) => unreachable!(),
+        None => {}
+    }
 
+    match matches.value_of("big-integers") {
+        Some("float") => config.big_integers = BigIntegers::Float,
+        Some("string") => config.big_integers = BigIntegers::String,
+        Some(_) => unreachable!(),
+        None => {}
+    }
+
+    match matches.value_of("nullable-fallback") {
+        Some("empty") => config.nullable_fallback = NullableFallback::Empty,
+        Some("base") => config.nullable_fallback = NullableFallback::Base,
+        Some(_) => unreachable!(),
+        None => {}
+    }
+
+    if let Some(max_size) = matches.value_of("max-enum-size") {
+        config.max_enum_size = Some(max_size.parse()?);
+    }
+
+    if let Some(max_size) = matches.value_of("integer-enums") {
+        config.integer_enums = Some(max_size.parse()?);
+    }
+
+    if let Some(min_length) = matches.value_of("detect-content-encodings") {
+        config.content_encodings = Some(min_length.parse()?);
+    }
+
+    if let Some(min_ratio) = matches.value_of("content-encoding-ratio") {
+        let min_ratio: f64 = min_ratio.parse()?;
+        if !(0.0..=1.0).contains(&min_ratio) {
+            bail!(
+                "--content-encoding-ratio must be between 0 and 1, got {}",
+                min_ratio
+            );
+        }
+
+        config.content_encoding_ratio = Some(min_ratio);
+    }
+
+    if let Some(size) = matches.value_of("format-sample-size") {
+        config.format_sample_size = Some(size.parse()?);
+    }
+
+    if let Some(max_tags) = matches.value_of("element-discriminators") {
+        config.element_discriminators = Some(max_tags.parse()?);
+    }
+
+    if let Some(min_optional) = matches.value_of("collapse-values") {
+        config.values_collapse = Some(min_optional.parse()?);
+    }
+
+    if let Some(min_samples) = matches.value_of("min-required-samples") {
+        config.min_required_samples = min_samples.parse()?;
+    }
+
+    let max_samples = match matches.value_of("max-samples") {
+        Some(max_samples) => max_samples.parse()?,
+        None => usize::MAX,
+    };
+
+    let sample_rate: f64 = match matches.value_of("sample-rate") {
+        Some(sample_rate) => sample_rate.parse()?,
+        None => 1.0,
+    };
+
+    if !(0.0..=1.0).contains(&sample_rate) {
+        bail!("--sample-rate must be between 0 and 1, got {}", sample_rate);
+    }
+
+    let mut rng = match matches.value_of("seed") {
+        Some(seed) => StdRng::seed_from_u64(seed.parse()?),
+        None => StdRng::from_entropy(),
+    };
+
+    let hints = config.hints();
     let mut inferrer = Inferrer::new(hints);
 
-    let stream = Deserializer::from_reader(reader);
-    for value in stream.into_iter() {
-        inferrer = inferrer.infer(value.map_err(|e| JsValue::from_str(&e.to_string()))?);
+    // Stats are only kept for a single inferrer, and so aren't tracked with
+    // --union, which infers from each group of examples separately.
+    let union_similarity: f64 = match matches.value_of("union-similarity") {
+        Some(similarity) => similarity.parse()?,
+        None => 0.5,
+    };
+
+    if !(0.0..=1.0).contains(&union_similarity) {
+        bail!(
+            "--union-similarity must be between 0 and 1, got {}",
+            union_similarity
+        );
     }
 
-    let serde_schema: jtd::SerdeSchema = inferrer.into_schema().into_serde_schema();
-    serde_json::to_string(&serde_schema).map_err(|e| JsValue::from_str(&e.to_string()))
+    let mut union = matches
+        .is_present("union")
+        .then(|| Union::new(&config, union_similarity));
+
+    let stats_file = matches.value_of("stats-file");
+    let verbose = log::log_enabled!(log::Level::Info);
+    if union.is_none() && (matches.is_present("stats") || stats_file.is_some() || verbose) {
+        inferrer = inferrer.tracking_stats();
+    }
+
+    let csv_options = CsvOptions {
+        empty_as_null: matches.value_of("csv-empty").unwrap() == "null",
+        sniff_types: matches.value_of("csv-sniff-types").unwrap() == "on",
+    };
+    let skip_errors = matches.is_present("skip-errors");
+    let warn_duplicate_keys = matches.is_present("warn-duplicate-keys");
+
+    // Whether to sample a record is decided as the stream is consumed, so the
+    // input never needs to be held in memory. Errors are always let through,
+    // so that a malformed record is reported even if it wouldn't have been
+    // sampled.
+    //
+    // Taking from the stream, rather than skipping values after the fact,
+    // means that nothing past the last sample is ever parsed.
+    //
+    // Each input is only opened once the ones before it have been read, and
+    // examples are read from each in turn, as a single stream.
+    let inputs: Vec<_> = matches.values_of("input").unwrap().collect();
+    let gzip = matches.is_present("gzip");
+    let several_inputs = inputs.len() > 1;
+    let stream = inputs.into_iter().flat_map(|input| {
+        info!("reading {}", if input == "-" { "stdin" } else { input });
+        let values = open_input(input, gzip).and_then(|reader| {
+            read_values(
+                matches.value_of("input-format").unwrap(),
+                matches.value_of("stdin-format").unwrap(),
+                csv_options,
+                skip_errors,
+                warn_duplicate_keys,
+                reader,
+            )
+        });
+
+        let values = match values {
+            Ok(values) => values,
+            Err(err) => Box::new(std::iter::once(Err(err))),
+        };
+
+        // With more than one input, errors say which one they came from.
+        values.map(move |value| match value {
+            Err(err) if several_inputs => Err(err.context(format!("while reading {}", input))),
+            value => value,
+        })
+    });
+
+    // Top-level scalars among objects and arrays are usually stray lines in a
+    // log, rather than what the schema should describe. Examples are numbered
+    // from 1, across every input, malformed ones included, so that they can
+    // be pointed out.
+    let skip_scalars = matches.is_present("skip-scalar-examples");
+    let mut scalars = Scalars::default();
+    let mut read = 0;
+    let stream = stream
+        .inspect(|_| read += 1)
+        .enumerate()
+        .filter(|(index, value)| match value {
+            Ok(checked) => !(scalars.observe(index + 1, &checked.value) && skip_scalars),
+            Err(_) => true,
+        })
+        .map(|(_, value)| value);
+
+    // Skipped records are dropped before sampling, so that they don't count
+    // towards --max-samples.
+    let mut skipped = 0;
+    let stream = stream.filter_map(|value| match value {
+        Err(err) if skip_errors => {
+            warn!("skipping a malformed example: {:#}", err);
+            skipped += 1;
+            None
+        }
+        value => Some(value),
+    });
+
+    let sampled = stream.filter(|value| value.is_err() || rng.gen_bool(sample_rate));
+    for value in sampled.take(max_samples) {
+        match &mut union {
+            Some(union) => union.infer_checked(value?),
+            None => inferrer = inferrer.infer_checked(value?),
+        }
+    }
+
+    // Warnings only ever go to stderr, so that stdout has nothing but the
+    // schema. Under --union, scalars among objects are a group of their own,
+    // rather than something that makes the root accept anything.
+    let warnings = match &union {
+        Some(union) => union.warnings(),
+        None => inferrer
+            .warnings()
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    };
+    let scalar_warning = match &union {
+        Some(_) if !skip_scalars => None,
+        _ => scalars.warning(skip_scalars),
+    };
+    if skipped > 0 {
+        warn!(
+            "skipped {} malformed example{}, which the schema doesn't account for",
+            skipped,
+            if skipped == 1 { "" } else { "s" }
+        );
+    }
+
+    if let Some(warning) = &scalar_warning {
+        warn!("{}", warning);
+    }
+
+    for warning in &warnings {
+        warn!("{}", warning);
+    }
+
+    // The stats have to be taken before the inferrer is turned into a schema,
+    // which consumes it.
+    if let Some(stats) = inferrer.stats() {
+        info!(
+            "inferred from {} of the {} example{} read",
+            stats.samples,
+            read,
+            if read == 1 { "" } else { "s" }
+        );
+        if verbose {
+            log_stats(&config, &stats);
+        }
+
+        let stats = serde_json::to_string(&stats)?;
+        if matches.is_present("stats") {
+            eprintln!("{}", stats);
+        }
+        if let Some(path) = stats_file {
+            std::fs::write(path, stats + "\n")?;
+        }
+    }
+
+    // Each skipped example counts as a warning of its own. No schema is output,
+    // so that nothing downstream goes on to use it.
+    let warned = skipped + usize::from(scalar_warning.is_some()) + warnings.len();
+    if warned > 0 && matches.is_present("warnings-as-errors") {
+        bail!(
+            "{} warning{} given, and --warnings-as-errors was passed",
+            warned,
+            if warned == 1 { " was" } else { "s were" }
+        );
+    }
+
+    let stamp = matches.is_present("stamp");
+    let schema = match union {
+        Some(union) => union.into_schema(stamp)?,
+        None if stamp => inferrer.into_stamped_schema()?,
+        None => inferrer.into_schema()?,
+    };
+
+    let schema = match matches.value_of("root-name") {
+        Some(name) => name_root(schema, name)?,
+        None => schema,
+    };
+
+    let output = match matches.value_of("format").unwrap() {
+        "jtd" => {
+            let serde_schema: jtd::SerdeSchema = schema.into_serde_schema();
+            serde_json::to_string(&serde_schema)? + "\n"
+        }
+        "jsonschema" => serde_json::to_string(&to_json_schema(&schema))? + "\n",
+        "rust" => to_rust(&schema),
+        _ => unreachable!(),
+    };
+
+    match matches.value_of("output") {
+        Some(path) => write_atomically(Path::new(path), &output),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
 }
 
+/// Writes `contents` to the file at `path`, creating any directories it's in.
+///
+/// The contents are written to a temporary file next to `path` first, which is
+/// then renamed over it, so that `path` never has partial output in it, even
+/// if writing fails part of the way through.
+fn write_atomically(path: &Path, contents: &str) -> Result<(), Error> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir).with_context(|| format!("can't create {}", dir.display()))?;
 
-fn parse_json_pointer(s: &str) -> Vec<String> {
-    if s == "" {
-        vec![]
-    } else {
-        s.replace("~1", "/")
-            .replace("!0", "~")
-            .split("/")
-            .skip(1)
-            .map(String::from)
+    let name = path
+        .file_name()
+        .with_context(|| format!("can't write to {}, which isn't a file", path.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = dir.join(temp_name);
+
+    let written = std::fs::write(&temp, contents).and_then(|()| std::fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+
+    written.with_context(|| format!("can't write to {}", path.display()))
+}
+
+/// Prints diagnostics to stderr, prefixed with their level, as in `warning:
+/// ...`. Which ones are printed is up to [`log::set_max_level`].
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let level = match record.level() {
+                log::Level::Warn => "warning".to_owned(),
+                level => level.as_str().to_lowercase(),
+            };
+            eprintln!("{}: {}", level, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Logs what the stats say about how the examples were inferred from: which
+/// enums fell back to plain types, which of the hints in `config` never applied
+/// to anything, and, in more detail, the range of the numbers at each part of
+/// the input, which decides how wide a number type they need.
+fn log_stats(config: &Config, stats: &Stats) {
+    for pointer in &stats.enum_fallbacks {
+        info!(
+            "the input at {:?} was inferred as a plain type, rather than as an enum",
+            pointer
+        );
+    }
+
+    // With no examples, no hint could have applied to anything.
+    if stats.samples > 0 {
+        let hints = [
+            ("enum", &config.enum_hints),
+            ("values", &config.values_hints),
+            ("discriminator", &config.discriminator_hints),
+            ("open object", &config.open_object_hints),
+            ("any", &config.any_hints),
+        ];
+        let hints = hints
+            .iter()
+            .flat_map(|&(name, paths)| paths.iter().map(move |path| (name, path)));
+        let type_hints = config.type_hints.iter().map(|(path, _)| ("type", path));
+        let number_type_hints = config
+            .number_type_hints
+            .iter()
+            .map(|(path, _)| ("number type", path));
+        let subtree_number_type_hints = config
+            .subtree_number_type_hints
+            .iter()
+            .map(|(path, _)| ("subtree number type", path));
+
+        for (name, path) in hints
+            .chain(type_hints)
+            .chain(number_type_hints)
+            .chain(subtree_number_type_hints)
+        {
+            if !stats.reaches(path) {
+                info!(
+                    "the {} hint at {:?} didn't apply to any part of the input",
+                    name,
+                    to_json_pointer(path)
+                );
+            }
+        }
+    }
+
+    for (pointer, position) in &stats.positions {
+        if let (Some(min), Some(max)) = (position.min, position.max) {
+            debug!(
+                "the numbers at {:?} ranged from {} to {}",
+                pointer, min, max
+            );
+        }
+    }
+}
+
+type Values = Box<dyn Iterator<Item = Result<CheckedValue, Error>>>;
+
+/// A stream of values that haven't been checked for repeated keys.
+type Unchecked = Box<dyn Iterator<Item = Result<Value, Error>>>;
+
+/// Parses a stream of example values out of `reader`, in the given input
+/// format.
+///
+/// `layout` is how JSON input is laid out, as with `--stdin-format`. See
+/// [`read_json`]. `csv_options` are only used for CSV input.
+///
+/// If `skip_errors` is set, JSON input is read one value per line, so that the
+/// stream can carry on past a line that isn't valid JSON. See
+/// [`read_json_lines`].
+///
+/// If `check_duplicates` is set, repeated keys in JSON input are kept track of,
+/// so that they can be warned about. The other formats never repeat keys.
+fn read_values(
+    format: &str,
+    layout: &str,
+    csv_options: CsvOptions,
+    skip_errors: bool,
+    check_duplicates: bool,
+    reader: impl Read + 'static,
+) -> Result<Values, Error> {
+    match format {
+        "json" if check_duplicates => read_json::<CheckedValue>(layout, skip_errors, reader),
+        "json" => read_json::<Value>(layout, skip_errors, reader),
+        _ => read_other(format, csv_options, reader),
+    }
+}
+
+/// Parses JSON input as either [`Value`]s or [`CheckedValue`]s, with the
+/// examples laid out as one of:
+///
+/// * `stream`, any number of values separated by whitespace, as read by
+///   [`read_json_stream`].
+/// * `ndjson`, one value per line, as read by [`read_json_lines`].
+/// * `array`, a single array, whose elements are the examples.
+/// * `single`, a single value, which is the one example.
+///
+/// Input that isn't laid out as declared is an error. In particular, an array
+/// under `single` is one example, and not an array of them.
+fn read_json<T: DeserializeOwned + Into<CheckedValue> + 'static>(
+    layout: &str,
+    skip_errors: bool,
+    reader: impl Read + 'static,
+) -> Result<Values, Error> {
+    match layout {
+        "stream" | "ndjson" if skip_errors => Ok(read_json_lines::<T>(reader)),
+        "array" | "single" if skip_errors => bail!(
+            "--skip-errors needs JSON input with one example per line, not --stdin-format {}",
+            layout
+        ),
+        "stream" => Ok(read_json_stream::<T>(reader)),
+        "ndjson" => Ok(read_json_lines::<T>(reader)),
+        "array" => {
+            let values: Vec<T> = serde_json::from_reader(reader)
+                .context("expected the input to be a single JSON array of examples")?;
+            Ok(Box::new(values.into_iter().map(|value| Ok(value.into()))))
+        }
+        "single" => {
+            let value: T = serde_json::from_reader(reader)
+                .context("expected the input to be a single JSON value")?;
+            Ok(Box::new(std::iter::once(Ok(value.into()))))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Parses a stream of example values in any format besides JSON.
+fn read_other(
+    format: &str,
+    csv_options: CsvOptions,
+    reader: impl Read + 'static,
+) -> Result<Values, Error> {
+    let values = match format {
+        "yaml" => read_yaml(reader),
+        "toml" => read_toml(reader),
+        "msgpack" => read_msgpack(reader),
+        "csv" => read_csv(csv_options, reader),
+        _ => unreachable!(),
+    }?;
+
+    Ok(Box::new(values.map(|value| value.map(CheckedValue::from))))
+}
+
+/// Parses a stream of JSON values, such as newline-delimited JSON, as either
+/// [`Value`]s or [`CheckedValue`]s.
+///
+/// A value that can't be parsed is reported along with which example it was
+/// and where in the input it starts, and ends the stream.
+fn read_json_stream<T: DeserializeOwned + Into<CheckedValue> + 'static>(
+    reader: impl Read + 'static,
+) -> Values {
+    let mut stream = Deserializer::from_reader(reader).into_iter::<T>();
+    let mut example = 0;
+    Box::new(std::iter::from_fn(move || {
+        let offset = stream.byte_offset();
+        let value = stream.next()?;
+        example += 1;
+
+        Some(value.map(T::into).map_err(|err| {
+            Error::from(err).context(format!(
+                "invalid JSON in example {}, which starts after byte {}",
+                example, offset
+            ))
+        }))
+    }))
+}
+
+/// Parses newline-delimited JSON, one value per line. Blank lines are
+/// ignored.
+///
+/// Unlike with [`read_json_stream`], a line that can't be parsed doesn't end the
+/// stream. It's reported along with its line number, and the stream carries on
+/// with the next line.
+fn read_json_lines<T: DeserializeOwned + Into<CheckedValue> + 'static>(
+    reader: impl Read + 'static,
+) -> Values {
+    use std::io::BufRead;
+
+    let lines = BufReader::new(reader).lines().enumerate();
+    Box::new(lines.filter_map(|(index, line)| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map(T::into).map_err(|err| {
+            Error::from(err).context(format!("invalid JSON on line {}", index + 1))
+        })),
+        Err(err) => Some(Err(err.into())),
+    }))
+}
+
+/// Parses YAML input. Each document in a multi-document stream is a separate
+/// example.
+#[cfg(feature = "yaml")]
+fn read_yaml(reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    use serde::Deserialize;
+
+    // serde_yaml reads all of the input up front, but each document is only
+    // parsed once the one before it has been inferred from.
+    Ok(Box::new(serde_yaml::Deserializer::from_reader(reader).map(
+        |document| Value::deserialize(document).map_err(Error::from),
+    )))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_yaml(_reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    bail!("jtd-infer was built without YAML support; enable the \"yaml\" feature")
+}
+
+/// Parses TOML input. A TOML file is always a single table, and so is a single
+/// example.
+#[cfg(feature = "toml")]
+fn read_toml(mut reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    fn to_json(value: toml::Value) -> Value {
+        match value {
+            toml::Value::String(s) => Value::String(s),
+            toml::Value::Integer(i) => Value::from(i),
+            toml::Value::Float(f) => Value::from(f),
+            toml::Value::Boolean(b) => Value::Bool(b),
+            toml::Value::Datetime(d) => Value::String(d.to_string()),
+            toml::Value::Array(vals) => Value::Array(vals.into_iter().map(to_json).collect()),
+            toml::Value::Table(table) => {
+                Value::Object(table.into_iter().map(|(k, v)| (k, to_json(v))).collect())
+            }
+        }
+    }
+
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let value = to_json(toml::from_str(&input)?);
+    Ok(Box::new(std::iter::once(Ok(value))))
+}
+
+#[cfg(not(feature = "toml"))]
+fn read_toml(_reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    bail!("jtd-infer was built without TOML support; enable the \"toml\" feature")
+}
+
+/// The examples that are scalars, as opposed to objects or arrays, so that
+/// they can be warned about when they're mixed.
+#[derive(Default)]
+struct Scalars {
+    /// The numbers of the first few scalar examples, counting from 1, to
+    /// list in warnings.
+    numbers: Vec<usize>,
+
+    /// How many examples were scalars.
+    count: usize,
+
+    /// Whether any example was an object or an array.
+    containers: bool,
+}
+
+impl Scalars {
+    /// Keeps track of `value`, as the example with the given number, and
+    /// returns whether it's a scalar. Nulls are neither scalars nor containers,
+    /// since they only make the root nullable.
+    fn observe(&mut self, number: usize, value: &Value) -> bool {
+        match value {
+            Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+                if self.numbers.len() < LISTED {
+                    self.numbers.push(number);
+                }
+
+                self.count += 1;
+                true
+            }
+            Value::Array(_) | Value::Object(_) => {
+                self.containers = true;
+                false
+            }
+            Value::Null => false,
+        }
+    }
+
+    /// A warning about the scalars, if they were mixed with objects or arrays,
+    /// or if they were `skipped`.
+    fn warning(&self, skipped: bool) -> Option<String> {
+        let (were, them) = match self.count {
+            0 => return None,
+            1 => ("was a scalar", "it"),
+            _ => ("were scalars", "them"),
+        };
+
+        if skipped {
+            Some(format!(
+                "skipped {}, which {}",
+                list_examples(&self.numbers, self.count),
+                were
+            ))
+        } else if self.containers {
+            Some(format!(
+                "{} {}, but others were objects or arrays, so any value is accepted at the root; pass --skip-scalar-examples to leave {} out",
+                list_examples(&self.numbers, self.count),
+                were,
+                them
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Examples inferred from separately, in groups of the same top-level shape,
+/// for `--union`.
+///
+/// Objects are grouped by the keys they have in common: an object joins the
+/// group of objects whose keys are the most like its own, as measured by
+/// [`Keys::similarity`], as long as that's at least `similarity`. Otherwise,
+/// it starts a new group. Every other kind of example is grouped by its kind
+/// alone, so that all arrays are inferred together. Groups are named after
+/// what's in them, and are numbered in the order they were first seen where a
+/// name would repeat, as in `object1` and `object2`.
+struct Union<'a> {
+    config: &'a Config,
+    similarity: f64,
+    groups: Vec<(String, Option<Keys>, Inferrer<'a>)>,
+    objects: usize,
+}
+
+impl<'a> Union<'a> {
+    fn new(config: &'a Config, similarity: f64) -> Self {
+        Self {
+            config,
+            similarity,
+            groups: vec![],
+            objects: 0,
+        }
+    }
+
+    fn infer_checked(&mut self, checked: CheckedValue) {
+        let kind = match &checked.value {
+            Value::Object(_) => "object",
+            Value::Array(_) => "array",
+            Value::String(_) => "string",
+            Value::Number(_) => "number",
+            Value::Bool(_) => "boolean",
+            Value::Null => "null",
+        };
+
+        let group = match &checked.value {
+            Value::Object(values) => self.object_group(values),
+            _ => self.groups.iter().position(|(name, _, _)| name == kind),
+        };
+
+        let index = group.unwrap_or_else(|| {
+            let (name, keys) = match kind {
+                "object" => {
+                    self.objects += 1;
+                    (format!("object{}", self.objects), Some(Keys::default()))
+                }
+                _ => (kind.to_owned(), None),
+            };
+
+            self.groups
+                .push((name, keys, Inferrer::new(self.config.hints())));
+            self.groups.len() - 1
+        });
+
+        let (_, keys, inferrer) = &mut self.groups[index];
+        if let (Some(keys), Value::Object(values)) = (keys, &checked.value) {
+            keys.insert(values);
+        }
+
+        inferrer.extend(std::iter::once(checked));
+    }
+
+    /// The group of objects whose keys are the most like those of `object`, if
+    /// any are similar enough. Ties go to the group seen first.
+    fn object_group(&self, object: &Map<String, Value>) -> Option<usize> {
+        let mut best = None;
+        for (index, (_, keys, _)) in self.groups.iter().enumerate() {
+            let similarity = match keys {
+                Some(keys) => keys.similarity(object),
+                None => continue,
+            };
+
+            let better = match best {
+                Some((_, best)) => similarity > best,
+                None => true,
+            };
+
+            if better && similarity >= self.similarity {
+                best = Some((index, similarity));
+            }
+        }
+
+        best.map(|(index, _)| index)
+    }
+
+    /// The warnings about each group, prefixed with the name of the group
+    /// where there's more than one.
+    fn warnings(&self) -> Vec<String> {
+        let several = self.groups.len() > 1;
+        self.groups
+            .iter()
+            .flat_map(|(name, _, inferrer)| {
+                inferrer.warnings().into_iter().map(move |warning| {
+                    if several {
+                        format!("among the {} examples, {}", name, warning)
+                    } else {
+                        warning.to_string()
+                    }
+                })
+            })
             .collect()
     }
+
+    /// The schema of each group as a definition, with the root accepting any
+    /// of them, and listing their names in its metadata as `union`. With only
+    /// one group, this is just its schema, and with none, it's the schema of
+    /// an inferrer that saw no examples.
+    fn into_schema(mut self, stamp: bool) -> Result<Schema, Error> {
+        if self.groups.is_empty() {
+            let inferrer = Inferrer::new(self.config.hints());
+            self.groups.push((String::new(), None, inferrer));
+        }
+
+        let mut schemas = Vec::new();
+        for (name, _, inferrer) in self.groups {
+            let schema = if stamp {
+                inferrer.into_stamped_schema()?
+            } else {
+                inferrer.into_schema()?
+            };
+            schemas.push((name, schema));
+        }
+
+        if schemas.len() == 1 {
+            return Ok(schemas.pop().unwrap().1);
+        }
+
+        let names = schemas
+            .iter()
+            .map(|(name, _)| Value::String(name.clone()))
+            .collect();
+        Ok(Schema::Empty {
+            definitions: schemas.into_iter().collect(),
+            metadata: vec![("union".to_owned(), Value::Array(names))]
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+/// The keys of the objects in a group of a [`Union`], with how many of the
+/// objects had each one.
+#[derive(Default)]
+struct Keys {
+    counts: BTreeMap<String, usize>,
+    objects: usize,
+}
+
+impl Keys {
+    fn insert(&mut self, object: &Map<String, Value>) {
+        for key in object.keys() {
+            *self.counts.entry(key.clone()).or_insert(0) += 1;
+        }
+
+        self.objects += 1;
+    }
+
+    /// How much `object` is like the objects in the group, from 0 to 1.
+    ///
+    /// This is the smaller of two shares: how many of the keys that most of
+    /// the objects had are in `object`, and how many of the keys in `object`
+    /// any of them had. Keys that only some objects have, like optional
+    /// properties, don't count against an object either way, so however many
+    /// of them there are, the objects stay one group. A share of no keys is 1.
+    fn similarity(&self, object: &Map<String, Value>) -> f64 {
+        let common: Vec<_> = self
+            .counts
+            .iter()
+            .filter(|(_, &count)| count * 2 > self.objects)
+            .map(|(key, _)| key)
+            .collect();
+
+        let has_common = common.iter().filter(|key| object.contains_key(**key));
+        let seen = object.keys().filter(|key| self.counts.contains_key(*key));
+
+        share(has_common.count(), common.len()).min(share(seen.count(), object.len()))
+    }
+}
+
+/// `part` out of `whole`, where nothing out of nothing is everything.
+fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        1.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// The most examples a warning lists by number.
+const LISTED: usize = 10;
+
+/// Describes `count` examples, such as "examples 3 and 7", for a warning.
+/// `numbers` are the numbers of the first of them, and only up to [`LISTED`]
+/// are listed.
+fn list_examples(numbers: &[usize], count: usize) -> String {
+    let (last, rest) = match numbers {
+        [number] if count == 1 => return format!("example {}", number),
+        [rest @ .., last] if count <= LISTED => (last.to_string(), rest),
+        _ => {
+            let listed = &numbers[..numbers.len().min(LISTED)];
+            (format!("{} more", count - listed.len()), listed)
+        }
+    };
+
+    let rest: Vec<_> = rest.iter().map(usize::to_string).collect();
+    format!("examples {} and {}", rest.join(", "), last)
+}
+
+/// Opens an input to read examples from, where `-` is stdin. It's decompressed
+/// if `gzip` is set, or if it's a file whose name ends in `.gz`.
+fn open_input(input: &str, gzip: bool) -> Result<BufReader<Box<dyn Read>>, Error> {
+    let reader = match input {
+        "-" => Box::new(stdin()) as Box<dyn Read>,
+        file => Box::new(File::open(file).with_context(|| format!("can't open {}", file))?)
+            as Box<dyn Read>,
+    };
+
+    let reader = if gzip || input.ends_with(".gz") {
+        gunzip(reader)?
+    } else {
+        reader
+    };
+
+    Ok(BufReader::new(reader))
+}
+
+/// Decompresses gzipped input as it's read, so that it's never decompressed
+/// in memory all at once. A file of several gzip members, one after another,
+/// is read as the concatenation of them, just as `gunzip` does.
+#[cfg(feature = "gzip")]
+fn gunzip(reader: Box<dyn Read>) -> Result<Box<dyn Read>, Error> {
+    Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_reader: Box<dyn Read>) -> Result<Box<dyn Read>, Error> {
+    bail!("jtd-infer was built without gzip support; enable the \"gzip\" feature")
+}
+
+/// Reads a [`Config`] from a JSON file, or from a YAML file if `path` ends in
+/// `.yaml` or `.yml`.
+fn read_config(path: &str) -> Result<Config, Error> {
+    let file = BufReader::new(File::open(path)?);
+    if path.ends_with(".yaml") || path.ends_with(".yml") {
+        read_yaml_config(file)
+    } else {
+        Ok(serde_json::from_reader(file)?)
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn read_yaml_config(reader: impl Read) -> Result<Config, Error> {
+    Ok(serde_yaml::from_reader(reader)?)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_yaml_config(_reader: impl Read) -> Result<Config, Error> {
+    bail!("jtd-infer was built without YAML support; enable the \"yaml\" feature")
+}
+
+/// Parses a stream of MessagePack values.
+///
+/// MessagePack binary and extension values have no equivalent in JSON, and so
+/// input containing them is rejected with an error.
+#[cfg(feature = "msgpack")]
+fn read_msgpack(reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    use serde::Deserialize;
+    use std::io::BufRead;
+
+    let mut reader = BufReader::new(reader);
+    let mut failed = false;
+    Ok(Box::new(std::iter::from_fn(move || {
+        // There's no telling where the next value starts after a malformed
+        // one, and so the stream ends there.
+        if failed {
+            return None;
+        }
+
+        // Input running out between values is the end of the stream. Running
+        // out in the middle of one is an error, and is reported by rmp-serde.
+        match reader.fill_buf() {
+            Ok([]) => return None,
+            Ok(_) => {}
+            Err(err) => return Some(Err(err.into())),
+        }
+
+        let value = Value::deserialize(&mut rmp_serde::Deserializer::new(&mut reader));
+        failed = value.is_err();
+        Some(value.map_err(|err| Error::from(err).context("invalid MessagePack input")))
+    })))
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn read_msgpack(_reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    bail!("jtd-infer was built without MessagePack support; enable the \"msgpack\" feature")
+}
+
+/// How to read the cells of CSV input.
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "csv"), allow(dead_code))]
+struct CsvOptions {
+    /// Whether empty cells become null, rather than being left out of the row.
+    empty_as_null: bool,
+
+    /// Whether cells that look like numbers or booleans are read as them,
+    /// rather than as strings.
+    sniff_types: bool,
+}
+
+/// Parses CSV input. The first row is a header, which gives the keys of the
+/// objects that each of the following rows becomes.
+///
+/// If `sniff_types` is set, cells that are valid JSON numbers, or `true` or
+/// `false`, become numbers and booleans. Everything else is a string. Empty
+/// cells are left out of the row's object, or become null if `empty_as_null`
+/// is set.
+#[cfg(feature = "csv")]
+fn read_csv(options: CsvOptions, reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+
+    Ok(Box::new(reader.into_records().map(move |record| {
+        let mut row = serde_json::Map::new();
+        for (header, cell) in headers.iter().zip(record?.iter()) {
+            let value = if cell.is_empty() {
+                if !options.empty_as_null {
+                    continue;
+                }
+
+                Value::Null
+            } else if !options.sniff_types {
+                Value::String(cell.to_owned())
+            } else if let Ok(n) = serde_json::from_str::<serde_json::Number>(cell) {
+                Value::Number(n)
+            } else if cell == "true" || cell == "false" {
+                Value::Bool(cell == "true")
+            } else {
+                Value::String(cell.to_owned())
+            };
+
+            row.insert(header.to_owned(), value);
+        }
+
+        Ok(Value::Object(row))
+    })))
+}
+
+#[cfg(not(feature = "csv"))]
+fn read_csv(_options: CsvOptions, _reader: impl Read + 'static) -> Result<Unchecked, Error> {
+    bail!("jtd-infer was built without CSV support; enable the \"csv\" feature")
+}
+
+/// Parses a type hint of the form `<pointer>=<type>`. The pointer may itself
+/// contain `=`, and so the input is split on the last one.
+fn parse_type_hint(s: &str) -> Result<(Vec<String>, Type), Error> {
+    let (pointer, type_) = match s.rsplit_once('=') {
+        Some(parts) => parts,
+        None => bail!(
+            "--type-hint must be of the form <pointer>=<type>, got {:?}",
+            s
+        ),
+    };
+
+    let type_ = match type_ {
+        "boolean" => Type::Boolean,
+        "string" => Type::String,
+        "timestamp" => Type::Timestamp,
+        "int8" => Type::Int8,
+        "uint8" => Type::Uint8,
+        "int16" => Type::Int16,
+        "uint16" => Type::Uint16,
+        "int32" => Type::Int32,
+        "uint32" => Type::Uint32,
+        "float32" => Type::Float32,
+        "float64" => Type::Float64,
+        _ => bail!("unknown type in --type-hint: {:?}", type_),
+    };
+
+    Ok((parse_json_pointer(pointer), type_))
+}
+
+/// Parses a number type hint of the form `<pointer>=<number type>`, the same
+/// way as [`parse_type_hint`], for the given flag.
+fn parse_number_type_hint(flag: &str, s: &str) -> Result<(Vec<String>, NumType), Error> {
+    let (pointer, num_type) = match s.rsplit_once('=') {
+        Some(parts) => parts,
+        None => bail!(
+            "--{} must be of the form <pointer>=<type>, got {:?}",
+            flag,
+            s
+        ),
+    };
+
+    match num_type.parse() {
+        Ok(num_type) => Ok((parse_json_pointer(pointer), num_type)),
+        Err(_) => bail!("unknown number type in --{}: {:?}", flag, num_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV_DEFAULTS: CsvOptions = CsvOptions {
+        empty_as_null: false,
+        sniff_types: true,
+    };
+
+    #[test]
+    fn read_json_errors() {
+        let input = "{ \"a\": 1 }\n{ \"a\": 2, }\n{ \"a\": 3 }\n";
+        let mut values = read_values(
+            "json",
+            "stream",
+            CSV_DEFAULTS,
+            false,
+            false,
+            input.as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::json!({ "a": 1 }),
+            values.next().unwrap().unwrap().value
+        );
+        assert_eq!(
+            "invalid JSON in example 2, which starts after byte 10: trailing comma at line 2 column 11",
+            format!("{:#}", values.next().unwrap().unwrap_err())
+        );
+        assert!(values.next().is_none());
+    }
+
+    #[test]
+    fn read_json_lines_errors() {
+        let input = "{ \"a\": 1 }\n{ \"a\": 2, }\n\n{ \"a\": 3 }\n";
+        let mut values = read_values(
+            "json",
+            "stream",
+            CSV_DEFAULTS,
+            true,
+            false,
+            input.as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::json!({ "a": 1 }),
+            values.next().unwrap().unwrap().value
+        );
+        assert_eq!(
+            "invalid JSON on line 2: trailing comma at line 1 column 11",
+            format!("{:#}", values.next().unwrap().unwrap_err())
+        );
+        assert_eq!(
+            serde_json::json!({ "a": 3 }),
+            values.next().unwrap().unwrap().value
+        );
+        assert!(values.next().is_none());
+    }
+
+    #[test]
+    fn read_json_duplicate_keys() {
+        let input = "{ \"a\": 1, \"a\": 2 }\n";
+        for skip_errors in [false, true] {
+            let checked = read_values(
+                "json",
+                "stream",
+                CSV_DEFAULTS,
+                skip_errors,
+                true,
+                input.as_bytes(),
+            )
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(serde_json::json!({ "a": 2 }), checked.value);
+            assert_eq!(vec![vec!["a".to_owned()]], checked.duplicate_keys);
+
+            let unchecked = read_values(
+                "json",
+                "stream",
+                CSV_DEFAULTS,
+                skip_errors,
+                false,
+                input.as_bytes(),
+            )
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+
+            assert!(unchecked.duplicate_keys.is_empty());
+        }
+    }
+
+    #[test]
+    fn union() {
+        let config = Config::default();
+        let mut union = Union::new(&config, 0.5);
+        for value in [
+            serde_json::json!({ "type": "click", "x": 1 }),
+            serde_json::json!({ "user": "alice" }),
+            serde_json::json!([1]),
+            serde_json::json!({ "x": 300, "type": "click" }),
+            serde_json::json!({}),
+            serde_json::json!(["a"]),
+        ] {
+            union.infer_checked(value.into());
+        }
+
+        assert_eq!(
+            vec![
+                "among the object1 examples, numbers at \"/x\" ranged from 1 to 300, which don't all fit in uint8, so they were inferred as uint16",
+                "among the array examples, examples at \"/-\" were both numbers and strings, so any value is accepted there",
+            ],
+            union.warnings()
+        );
+        assert_eq!(
+            serde_json::json!({
+                "metadata": { "union": ["object1", "object2", "array", "object3"] },
+                "definitions": {
+                    "object1": {
+                        "properties": {
+                            "type": { "type": "string" },
+                            "x": { "type": "uint16" },
+                        },
+                    },
+                    "object2": { "properties": { "user": { "type": "string" } } },
+                    "array": { "elements": {} },
+                    "object3": {},
+                },
+            }),
+            serde_json::to_value(union.into_schema(false).unwrap().into_serde_schema()).unwrap()
+        );
+
+        // With only one group, or none, there's no union.
+        assert_eq!(
+            serde_json::json!({}),
+            serde_json::to_value(
+                Union::new(&config, 0.5)
+                    .into_schema(false)
+                    .unwrap()
+                    .into_serde_schema()
+            )
+            .unwrap()
+        );
+
+        let mut union = Union::new(&config, 0.5);
+        union.infer_checked(serde_json::json!({ "a": 1 }).into());
+        assert_eq!(
+            serde_json::json!({ "properties": { "a": { "type": "uint8" } } }),
+            serde_json::to_value(union.into_schema(false).unwrap().into_serde_schema()).unwrap()
+        );
+    }
+
+    #[test]
+    fn union_similarity() {
+        let config = Config::default();
+        let names = |similarity, values: &[Value]| {
+            let mut union = Union::new(&config, similarity);
+            for value in values {
+                union.infer_checked(value.clone().into());
+            }
+
+            let names: Vec<_> = union
+                .groups
+                .iter()
+                .map(|(name, _, _)| name.clone())
+                .collect();
+            names
+        };
+
+        // Optional properties don't split a group, however many combinations
+        // of them there are.
+        let events: Vec<_> = (0..16)
+            .map(|i| {
+                let mut event = serde_json::json!({ "type": "click", "x": 1, "y": 2 });
+                for bit in 0..4 {
+                    if i & (1 << bit) != 0 {
+                        event[format!("extra{}", bit)] = serde_json::json!(true);
+                    }
+                }
+
+                event
+            })
+            .collect();
+        assert_eq!(vec!["object1"], names(0.5, &events));
+
+        // An object joins the group that's most like it.
+        let values = [
+            serde_json::json!({ "a": 1, "b": 1 }),
+            serde_json::json!({ "c": 1, "d": 1, "e": 1 }),
+            serde_json::json!({ "c": 1, "d": 1 }),
+            serde_json::json!({ "a": 1, "c": 1, "d": 1 }),
+        ];
+        assert_eq!(vec!["object1", "object2"], names(0.5, &values));
+        assert_eq!(
+            vec!["object1", "object2", "object3", "object4"],
+            names(1.0, &values)
+        );
+        assert_eq!(vec!["object1"], names(0.0, &values));
+    }
+
+    #[test]
+    fn stray_scalars() {
+        // Mostly objects, with a couple of stray numbers.
+        let input = "{ \"a\": 1 }\n42\n{ \"a\": 2 }\n\n{ \"a\": 3 }\n7\n";
+        let values: Vec<_> = read_values(
+            "json",
+            "ndjson",
+            CSV_DEFAULTS,
+            false,
+            false,
+            input.as_bytes(),
+        )
+        .unwrap()
+        .map(|value| value.unwrap().value)
+        .collect();
+
+        let mut scalars = Scalars::default();
+        let kept: Vec<_> = values
+            .iter()
+            .enumerate()
+            .filter(|(index, value)| !scalars.observe(index + 1, value))
+            .map(|(_, value)| value.clone())
+            .collect();
+
+        assert_eq!(vec![2, 5], scalars.numbers);
+        assert_eq!(2, scalars.count);
+        assert_eq!(
+            Some("examples 2 and 5 were scalars, but others were objects or arrays, so any value is accepted at the root; pass --skip-scalar-examples to leave them out".to_owned()),
+            scalars.warning(false)
+        );
+        assert_eq!(
+            Some("skipped examples 2 and 5, which were scalars".to_owned()),
+            scalars.warning(true)
+        );
+
+        // Inferred from as they are, the root accepts anything. Without them,
+        // it's the objects' schema.
+        let infer = |values: Vec<Value>| {
+            values
+                .into_iter()
+                .fold(Inferrer::new(Default::default()), Inferrer::infer)
+                .into_schema()
+                .unwrap()
+                .into_serde_schema()
+        };
+        assert_eq!(
+            serde_json::json!({}),
+            serde_json::to_value(infer(values)).unwrap()
+        );
+        assert_eq!(
+            serde_json::json!({ "properties": { "a": { "type": "uint8" } } }),
+            serde_json::to_value(infer(kept)).unwrap()
+        );
+
+        // Scalars on their own, or alongside nulls, aren't a mix.
+        let mut scalars = Scalars::default();
+        scalars.observe(1, &serde_json::json!("a"));
+        scalars.observe(2, &Value::Null);
+        assert_eq!(None, scalars.warning(false));
+
+        // Only the first few are kept, however many there are.
+        let mut scalars = Scalars::default();
+        for number in 1..=1000 {
+            scalars.observe(number, &serde_json::json!(1));
+        }
+        assert_eq!(LISTED, scalars.numbers.len());
+        assert_eq!(
+            Some(
+                "skipped examples 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 and 990 more, which were scalars"
+                    .to_owned()
+            ),
+            scalars.warning(true)
+        );
+    }
+
+    #[test]
+    fn write_atomically() {
+        let dir = std::env::temp_dir().join(format!("jtd-infer-test-{}", std::process::id()));
+        let path = dir.join("nested").join("schema.json");
+
+        // Directories are created as needed, and files are replaced.
+        super::write_atomically(&path, "{}\n").unwrap();
+        super::write_atomically(&path, "{\"type\":\"string\"}\n").unwrap();
+        assert_eq!(
+            "{\"type\":\"string\"}\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+
+        // The temporary file is renamed away.
+        let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(vec!["schema.json"], entries);
+
+        // A directory can't be written over with a file.
+        assert!(super::write_atomically(&dir.join("nested"), "{}").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn list_examples() {
+        assert_eq!("example 3", super::list_examples(&[3], 1));
+        assert_eq!("examples 3 and 7", super::list_examples(&[3, 7], 2));
+        assert_eq!("examples 1, 2 and 3", super::list_examples(&[1, 2, 3], 3));
+
+        let first: Vec<_> = (1..=10).collect();
+        assert_eq!(
+            "examples 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 and 2 more",
+            super::list_examples(&first, 12)
+        );
+        assert_eq!(
+            "examples 1, 2, 3, 4, 5, 6, 7, 8, 9 and 10",
+            super::list_examples(&first, 10)
+        );
+    }
+
+    #[test]
+    fn read_json_layouts() {
+        let read = |layout, input: &'static str| {
+            read_values("json", layout, CSV_DEFAULTS, false, true, input.as_bytes())
+                .and_then(|values| values.map(|value| Ok(value?.value)).collect())
+                .map_err(|err| format!("{:#}", err))
+        };
+
+        // A single array is one example, unless the layout says otherwise.
+        let input = "[{ \"a\": 1 }, { \"a\": 2 }]";
+        let array = serde_json::json!([{ "a": 1 }, { "a": 2 }]);
+        assert_eq!(Ok(vec![array.clone()]), read("stream", input));
+        assert_eq!(Ok(vec![array.clone()]), read("single", input));
+        assert_eq!(Ok(vec![array.clone()]), read("ndjson", input));
+        assert_eq!(
+            Ok(vec![array[0].clone(), array[1].clone()]),
+            read("array", input)
+        );
+
+        // Repeated keys are relative to each element of an array.
+        let checked = read_values(
+            "json",
+            "array",
+            CSV_DEFAULTS,
+            false,
+            true,
+            "[{}, { \"a\": 1, \"a\": 2 }]".as_bytes(),
+        )
+        .unwrap()
+        .nth(1)
+        .unwrap()
+        .unwrap();
+        assert_eq!(vec![vec!["a".to_owned()]], checked.duplicate_keys);
+
+        // Input that isn't laid out as declared is rejected.
+        assert_eq!(
+            Err("expected the input to be a single JSON array of examples: invalid type: map, expected a sequence at line 1 column 1".to_owned()),
+            read("array", "{ \"a\": 1 }")
+        );
+        assert_eq!(
+            Err("expected the input to be a single JSON value: trailing characters at line 2 column 1".to_owned()),
+            read("single", "{ \"a\": 1 }\n{ \"a\": 2 }")
+        );
+        assert_eq!(
+            Err(
+                "invalid JSON on line 1: EOF while parsing an object at line 1 column 1".to_owned()
+            ),
+            read("ndjson", "{\n  \"a\": 1\n}")
+        );
+        assert_eq!(
+            Ok(vec![serde_json::json!({ "a": 1 })]),
+            read("stream", "{\n  \"a\": 1\n}")
+        );
+
+        assert!(read_values("json", "array", CSV_DEFAULTS, true, false, "[]".as_bytes()).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn read_gzip() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        // Two gzip members, one after the other, as from `cat a.gz b.gz`.
+        let mut input = vec![];
+        for part in ["{ \"a\": 1 }\n", "{ \"a\": 2 }\n"] {
+            let mut encoder = GzEncoder::new(vec![], Default::default());
+            encoder.write_all(part.as_bytes()).unwrap();
+            input.extend(encoder.finish().unwrap());
+        }
+
+        let reader = gunzip(Box::new(std::io::Cursor::new(input))).unwrap();
+        let values: Vec<_> = read_values("json", "ndjson", CSV_DEFAULTS, false, false, reader)
+            .unwrap()
+            .map(|value| value.unwrap().value)
+            .collect();
+
+        assert_eq!(
+            vec![serde_json::json!({ "a": 1 }), serde_json::json!({ "a": 2 })],
+            values
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn read_yaml_documents() {
+        let input = "a: 1\n---\n- x\n- true\n---\nb: [\n";
+        let mut values = read_values(
+            "yaml",
+            "stream",
+            CSV_DEFAULTS,
+            false,
+            false,
+            input.as_bytes(),
+        )
+        .unwrap()
+        .map(|value| value.map(|value| value.value));
+
+        assert_eq!(
+            serde_json::json!({ "a": 1 }),
+            values.next().unwrap().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(["x", true]),
+            values.next().unwrap().unwrap()
+        );
+        assert!(values.next().unwrap().is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn read_toml_table() {
+        let input = "a = 1\nb = 1979-05-27T07:32:00Z\n\n[c]\nd = [1.5, \"x\"]\n";
+        let values: Vec<_> = read_values(
+            "toml",
+            "stream",
+            CSV_DEFAULTS,
+            false,
+            false,
+            input.as_bytes(),
+        )
+        .unwrap()
+        .map(|value| value.unwrap().value)
+        .collect();
+
+        assert_eq!(
+            vec![serde_json::json!({
+                "a": 1,
+                "b": "1979-05-27T07:32:00Z",
+                "c": { "d": [1.5, "x"] },
+            })],
+            values
+        );
+
+        assert!(read_values(
+            "toml",
+            "stream",
+            CSV_DEFAULTS,
+            false,
+            false,
+            "a = ".as_bytes()
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn read_csv_types() {
+        let input = "a,b,c\n1,true,\n";
+        let read = |options| {
+            read_values("csv", "stream", options, false, false, input.as_bytes())
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap()
+                .value
+        };
+
+        assert_eq!(serde_json::json!({ "a": 1, "b": true }), read(CSV_DEFAULTS));
+        assert_eq!(
+            serde_json::json!({ "a": "1", "b": "true", "c": null }),
+            read(CsvOptions {
+                empty_as_null: true,
+                sniff_types: false,
+            })
+        );
+    }
 }
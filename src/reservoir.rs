@@ -0,0 +1,81 @@
+/// A bounded sample of the strings seen at a position, for the format
+/// detectors to go over once the schema is made.
+///
+/// This is reservoir sampling: the first `capacity` strings are all kept, and
+/// after that, each string replaces one already kept with a probability that
+/// leaves every string seen equally likely to be in the sample. The choices
+/// are made by hashing how many strings were seen, rather than at random, so
+/// that the same examples always make for the same schema.
+#[derive(Debug)]
+pub(crate) struct Reservoir {
+    samples: Vec<String>,
+    capacity: usize,
+    seen: usize,
+}
+
+impl Reservoir {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            samples: Vec::new(),
+            capacity,
+            seen: 0,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, s: String) {
+        self.seen += 1;
+
+        if self.samples.len() < self.capacity {
+            self.samples.push(s);
+        } else {
+            let i = below(self.seen as u64, self.seen);
+            if let Some(sample) = self.samples.get_mut(i) {
+                *sample = s;
+            }
+        }
+    }
+
+    /// The strings kept, in no particular order.
+    pub(crate) fn samples(&self) -> Vec<&str> {
+        self.samples.iter().map(String::as_str).collect()
+    }
+}
+
+/// A number less than `n`, chosen by hashing `x`.
+fn below(x: u64, n: usize) -> usize {
+    // The finalizer of SplitMix64, which spreads consecutive inputs well.
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+
+    (z % n as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reservoir(capacity: usize, strings: impl Iterator<Item = usize>) -> Reservoir {
+        let mut reservoir = Reservoir::new(capacity);
+        for s in strings {
+            reservoir.insert(s.to_string());
+        }
+
+        reservoir
+    }
+
+    #[test]
+    fn bounded() {
+        assert_eq!(vec!["0", "1", "2"], reservoir(4, 0..3).samples());
+
+        // Past its capacity, a reservoir keeps strings from all through the
+        // input, not just the first ones.
+        let sampled = reservoir(100, 0..10_000);
+        let samples = sampled.samples();
+        assert_eq!(100, samples.len());
+        assert!(samples.iter().any(|s| s.parse::<usize>().unwrap() >= 5_000));
+
+        assert!(reservoir(0, 0..10).samples().is_empty());
+    }
+}
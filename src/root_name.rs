@@ -0,0 +1,110 @@
+use crate::error::InferError;
+use crate::stamp::{metadata_mut, STAMP_KEY};
+use jtd::Schema;
+use std::collections::BTreeMap;
+
+/// Moves `schema` into a definition called `name`, and makes the root a `ref`
+/// to it.
+///
+/// This gives code generators a meaningful name for the root type:
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::{name_root, Hints, Inferrer};
+///
+/// let schema = Inferrer::new(Hints::default())
+///     .infer(json!({ "name": "Alice" }))
+///     .into_schema()
+///     .unwrap();
+///
+/// assert_eq!(
+///     json!({
+///         "definitions": {
+///             "User": { "properties": { "name": { "type": "string" } } },
+///         },
+///         "ref": "User",
+///     }),
+///     serde_json::to_value(name_root(schema, "User").unwrap().into_serde_schema()).unwrap(),
+/// )
+/// ```
+///
+/// JSON Typedef only allows definitions at the root, and so any definitions
+/// `schema` already has are kept at the root alongside the new one. If one of
+/// them is already called `name`, this returns
+/// [`InferError::DuplicateDefinition`]. A stamp from
+/// [`Inferrer::into_stamped_schema`][`crate::Inferrer::into_stamped_schema`]
+/// describes the whole schema, and so it stays at the root too.
+pub fn name_root(mut schema: Schema, name: &str) -> Result<Schema, InferError> {
+    let mut definitions = std::mem::take(definitions_mut(&mut schema));
+    if definitions.contains_key(name) {
+        return Err(InferError::DuplicateDefinition(name.to_owned()));
+    }
+
+    let mut metadata = BTreeMap::new();
+    if let Some(stamp) = metadata_mut(&mut schema).remove(STAMP_KEY) {
+        metadata.insert(STAMP_KEY.to_owned(), stamp);
+    }
+
+    definitions.insert(name.to_owned(), schema);
+    Ok(Schema::Ref {
+        definitions,
+        metadata,
+        nullable: false,
+        ref_: name.to_owned(),
+    })
+}
+
+fn definitions_mut(schema: &mut Schema) -> &mut BTreeMap<String, Schema> {
+    match schema {
+        Schema::Empty { definitions, .. }
+        | Schema::Ref { definitions, .. }
+        | Schema::Type { definitions, .. }
+        | Schema::Enum { definitions, .. }
+        | Schema::Elements { definitions, .. }
+        | Schema::Properties { definitions, .. }
+        | Schema::Values { definitions, .. }
+        | Schema::Discriminator { definitions, .. } => definitions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_definitions_and_stamp_at_root() {
+        let schema = Schema::from_serde_schema(
+            serde_json::from_value(json!({
+                "definitions": { "Name": { "type": "string" } },
+                "metadata": { "jtdInfer": { "samples": 1 }, "note": "x" },
+                "properties": { "name": { "ref": "Name" } },
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        let schema = name_root(schema, "User").unwrap();
+        schema.validate().unwrap();
+
+        assert_eq!(
+            json!({
+                "definitions": {
+                    "Name": { "type": "string" },
+                    "User": {
+                        "metadata": { "note": "x" },
+                        "properties": { "name": { "ref": "Name" } },
+                    },
+                },
+                "metadata": { "jtdInfer": { "samples": 1 } },
+                "ref": "User",
+            }),
+            serde_json::to_value(schema.clone().into_serde_schema()).unwrap()
+        );
+
+        assert_eq!(
+            Err(InferError::DuplicateDefinition("Name".to_owned())),
+            name_root(schema, "Name")
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use jtd::{Schema, Type};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const DERIVES: &str = "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]";
+const ENUM_DERIVES: &str = "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]";
+
+/// Generates Rust types, with serde derives, for the data a JSON Typedef schema
+/// describes.
+///
+/// The output is a starting point for hand-written models, rather than code
+/// that's meant to be regenerated. The forms of JSON Typedef become:
+///
+/// * `properties` a struct, with a field for each property. Optional properties
+///   are `Option`s that are left out when serializing `None`.
+/// * `discriminator` an internally tagged enum, with a struct variant for each
+///   mapping.
+/// * `enum` an enum of unit variants.
+/// * `elements` a `Vec`, and `values` a `BTreeMap` with string keys.
+/// * `nullable` an `Option`, and the empty form `serde_json::Value`.
+/// * `timestamp` a `String`, so that no particular date and time crate is
+///   needed. The number types become the Rust number types of the same size.
+///
+/// The root is named `Root`, and each definition after its name. Types nested
+/// within them are named after the path to them, such as `RootAddress` for the
+/// `address` property of the root. Nested types that come out the same are
+/// only generated once. Names that aren't valid Rust identifiers, or don't
+/// follow Rust's conventions, are adjusted, with a `#[serde(rename)]` to keep
+/// the original.
+///
+/// ```
+/// use serde_json::json;
+/// use jtd::Schema;
+/// use jtd_infer::to_rust;
+///
+/// let schema = Schema::from_serde_schema(serde_json::from_value(json!({
+///     "properties": { "firstName": { "type": "string" } },
+///     "optionalProperties": { "age": { "type": "uint8" } },
+/// })).unwrap()).unwrap();
+///
+/// assert_eq!(
+///     "use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// pub struct Root {
+///     #[serde(rename = \"firstName\")]
+///     pub first_name: String,
+///     #[serde(skip_serializing_if = \"Option::is_none\")]
+///     pub age: Option<u8>,
+/// }
+/// ",
+///     to_rust(&schema),
+/// );
+/// ```
+pub fn to_rust(schema: &Schema) -> String {
+    let mut generator = Generator::default();
+
+    // The root, if it needs a type of its own, and then the definitions get
+    // their names before anything nested within them, so that they're never
+    // taken.
+    let root_name = match schema {
+        Schema::Ref {
+            nullable: false, ..
+        } => None,
+        _ => Some(generator.unique_name("Root")),
+    };
+
+    for name in schema.definitions().keys() {
+        let type_name = generator.unique_name(&pascal_case(name));
+        generator.definitions.insert(name.clone(), type_name);
+    }
+
+    for (name, sub_schema) in schema.definitions() {
+        let type_name = generator.definitions[name].clone();
+        generator.named_type(sub_schema, &type_name);
+    }
+
+    if let Some(root_name) = root_name {
+        generator.named_type(schema, &root_name);
+    }
+
+    let mut out = "use serde::{Deserialize, Serialize};\n".to_owned();
+    for item in generator.items.iter().rev() {
+        out.push('\n');
+        out.push_str(item);
+    }
+
+    out
+}
+
+#[derive(Default)]
+struct Generator {
+    /// The Rust type name of each definition.
+    definitions: BTreeMap<String, String>,
+
+    /// The code of each item generated so far, in the order they were
+    /// generated. Nested types come before the types they're nested in.
+    items: Vec<String>,
+
+    /// The names of the items generated so far, keyed by their code with the
+    /// name left out, so that identical ones can be reused.
+    by_shape: HashMap<String, String>,
+
+    names: BTreeSet<String>,
+
+    /// The name, already taken, that the item being generated by
+    /// [`Generator::named_type`] gets.
+    naming: Option<String>,
+}
+
+impl Generator {
+    /// Generates a type called `name`, which must already be taken, for
+    /// `schema`. It's an alias if `schema` isn't one that's generated as an
+    /// item, or comes out the same as an item that already exists.
+    fn named_type(&mut self, schema: &Schema, name: &str) {
+        let is_item = matches!(
+            schema,
+            Schema::Enum { .. } | Schema::Properties { .. } | Schema::Discriminator { .. }
+        );
+
+        // A nullable item can't have the name of the alias that makes it
+        // nullable. Anything nested in something that isn't an item already
+        // has a name of its own.
+        let type_ = if !is_item {
+            self.type_of(schema, name)
+        } else if schema.nullable() {
+            self.type_of(schema, &format!("{}Value", name))
+        } else {
+            self.naming = Some(name.to_owned());
+            let type_ = self.type_of(schema, name);
+            self.naming = None;
+            type_
+        };
+
+        if type_ != name {
+            self.push_alias(name, &type_);
+        }
+    }
+
+    /// The Rust type for `schema`, generating any items it needs. `name` is
+    /// what to call an item for `schema` itself, and what to name the items
+    /// nested within it after.
+    fn type_of(&mut self, schema: &Schema, name: &str) -> String {
+        let type_ = match schema {
+            Schema::Empty { .. } => return "serde_json::Value".to_owned(),
+            Schema::Ref { ref_, .. } => self.definitions[ref_].clone(),
+            Schema::Type { type_, .. } => rust_type(type_).to_owned(),
+            Schema::Enum { enum_, .. } => {
+                let mut variants = BTreeSet::new();
+                let mut body = String::new();
+                for value in enum_ {
+                    let variant = unique(&mut variants, &pascal_case(value));
+                    push_rename(&mut body, "    ", &variant, value);
+                    body.push_str(&format!("    {},\n", variant));
+                }
+
+                self.item(ENUM_DERIVES, "enum", name, &body)
+            }
+            Schema::Elements { elements, .. } => {
+                format!("Vec<{}>", self.type_of(elements, &format!("{}Item", name)))
+            }
+            Schema::Values { values, .. } => format!(
+                "std::collections::BTreeMap<String, {}>",
+                self.type_of(values, &format!("{}Value", name))
+            ),
+            Schema::Properties { .. } => {
+                let body = self.fields(schema, name, "    ", "pub ");
+                self.item(DERIVES, "struct", name, &body)
+            }
+            Schema::Discriminator {
+                discriminator,
+                mapping,
+                ..
+            } => {
+                let mut variants = BTreeSet::new();
+                let mut body = String::new();
+                for (tag, sub_schema) in mapping {
+                    let variant = unique(&mut variants, &pascal_case(tag));
+                    let fields =
+                        self.fields(sub_schema, &format!("{}{}", name, variant), "        ", "");
+
+                    push_rename(&mut body, "    ", &variant, tag);
+                    if fields.is_empty() {
+                        body.push_str(&format!("    {} {{}},\n", variant));
+                    } else {
+                        body.push_str(&format!("    {} {{\n{}    }},\n", variant, fields));
+                    }
+                }
+
+                let attributes = format!("{}\n#[serde(tag = {:?})]", DERIVES, discriminator);
+                self.item(&attributes, "enum", name, &body)
+            }
+        };
+
+        if schema.nullable() {
+            format!("Option<{}>", type_)
+        } else {
+            type_
+        }
+    }
+
+    /// The fields of a struct, or struct variant, for a properties form.
+    fn fields(&mut self, schema: &Schema, name: &str, indent: &str, visibility: &str) -> String {
+        let (properties, optional_properties) = match schema {
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => (properties, optional_properties),
+            _ => unreachable!("non-properties fields"),
+        };
+
+        let mut field_names = BTreeSet::new();
+        let mut body = String::new();
+        let all = properties.iter().map(|p| (p, false));
+        for ((key, sub_schema), optional) in
+            all.chain(optional_properties.iter().map(|p| (p, true)))
+        {
+            let field = unique(&mut field_names, &snake_case(key));
+            let mut type_ = self.type_of(sub_schema, &format!("{}{}", name, pascal_case(key)));
+
+            push_rename(&mut body, indent, &field, key);
+            if optional {
+                body.push_str(&format!(
+                    "{}#[serde(skip_serializing_if = \"Option::is_none\")]\n",
+                    indent
+                ));
+
+                // A property that's both optional and nullable is represented
+                // by a single Option, so null and missing come out the same.
+                if matches!(sub_schema, Schema::Empty { .. }) || !sub_schema.nullable() {
+                    type_ = format!("Option<{}>", type_);
+                }
+            }
+
+            body.push_str(&format!("{}{}{}: {},\n", indent, visibility, field, type_));
+        }
+
+        body
+    }
+
+    /// Generates an item, unless an identical one already exists, and returns
+    /// its name.
+    fn item(&mut self, attributes: &str, keyword: &str, name: &str, body: &str) -> String {
+        let shape = format!("{}\n{}\n{}", attributes, keyword, body);
+        if let Some(existing) = self.by_shape.get(&shape) {
+            return existing.clone();
+        }
+
+        let name = match self.naming.take() {
+            Some(naming) if naming == name => naming,
+            naming => {
+                self.naming = naming;
+                self.unique_name(name)
+            }
+        };
+
+        self.items.push(format!(
+            "{}\npub {} {} {{\n{}}}\n",
+            attributes, keyword, name, body
+        ));
+        self.by_shape.insert(shape, name.clone());
+        name
+    }
+
+    fn push_alias(&mut self, name: &str, type_: &str) {
+        self.items.push(format!("pub type {} = {};\n", name, type_));
+    }
+
+    fn unique_name(&mut self, name: &str) -> String {
+        unique(&mut self.names, name)
+    }
+}
+
+/// Adds `name` to `taken`, with a number at the end if it's already there.
+fn unique(taken: &mut BTreeSet<String>, name: &str) -> String {
+    let mut candidate = name.to_owned();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", name, n);
+        n += 1;
+    }
+
+    taken.insert(candidate.clone());
+    candidate
+}
+
+fn push_rename(body: &mut String, indent: &str, ident: &str, original: &str) {
+    if ident != original {
+        body.push_str(&format!("{}#[serde(rename = {:?})]\n", indent, original));
+    }
+}
+
+fn rust_type(type_: &Type) -> &'static str {
+    match type_ {
+        Type::Boolean => "bool",
+        Type::Float32 => "f32",
+        Type::Float64 => "f64",
+        Type::Int8 => "i8",
+        Type::Uint8 => "u8",
+        Type::Int16 => "i16",
+        Type::Uint16 => "u16",
+        Type::Int32 => "i32",
+        Type::Uint32 => "u32",
+        Type::String | Type::Timestamp => "String",
+    }
+}
+
+/// Splits a name into words, at anything that isn't a letter or digit, and
+/// where a lowercase letter or digit is followed by an uppercase one.
+fn words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut prev_lower = false;
+
+    for c in s.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        } else {
+            if c.is_uppercase() && prev_lower && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+
+            word.push(c);
+        }
+
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+fn pascal_case(s: &str) -> String {
+    let ident: String = words(s)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap();
+            first.to_uppercase().chain(chars).collect::<String>()
+        })
+        .collect();
+
+    valid_ident(ident)
+}
+
+fn snake_case(s: &str) -> String {
+    let ident = words(s)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    valid_ident(ident)
+}
+
+/// Makes `ident` a valid Rust identifier, if it isn't already one.
+fn valid_ident(ident: String) -> String {
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else if is_keyword(&ident) {
+        format!("{}_", ident)
+    } else {
+        ident
+    }
+}
+
+/// Whether `s` is a Rust keyword, including the ones reserved for future use.
+fn is_keyword(s: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+        "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+        "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+        "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try",
+        "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+
+    KEYWORDS.contains(&s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn convert_serde(schema: Value) -> String {
+        let schema = Schema::from_serde_schema(serde_json::from_value(schema).unwrap()).unwrap();
+        let rust = to_rust(&schema);
+        rust.strip_prefix("use serde::{Deserialize, Serialize};\n\n")
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn structs() {
+        assert_eq!(
+            r#"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Root {
+    pub address: RootAddress,
+    pub billing: RootAddress,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(rename = "Tags")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "extra-data")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RootAddress {
+    pub street: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zip: Option<u32>,
+}
+"#
+            .trim_start(),
+            convert_serde(json!({
+                "properties": {
+                    "address": {
+                        "properties": { "street": { "type": "string" } },
+                        "optionalProperties": { "zip": { "type": "uint32" } },
+                    },
+                    "billing": {
+                        "properties": { "street": { "type": "string" } },
+                        "optionalProperties": { "zip": { "type": "uint32" } },
+                    },
+                    "type": { "type": "timestamp", "nullable": true },
+                },
+                "optionalProperties": {
+                    "Tags": { "elements": { "type": "string" } },
+                    "extra-data": {},
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn enums() {
+        assert_eq!(
+            r#"
+pub type Root = std::collections::BTreeMap<String, Vec<RootValueItem>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RootValueItem {
+    #[serde(rename = "1")]
+    _1,
+    Done,
+    #[serde(rename = "in-progress")]
+    InProgress,
+}
+"#
+            .trim_start(),
+            convert_serde(json!({
+                "values": { "elements": { "enum": ["in-progress", "Done", "1"] } },
+            }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            r#"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Root {
+    #[serde(rename = "circle")]
+    Circle {
+        center: RootCircleCenter,
+        radius: f64,
+    },
+    #[serde(rename = "empty")]
+    Empty {},
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RootCircleCenter {
+    pub x: f32,
+}
+"#
+            .trim_start(),
+            convert_serde(json!({
+                "discriminator": "kind",
+                "mapping": {
+                    "circle": {
+                        "properties": {
+                            "radius": { "type": "float64" },
+                            "center": { "properties": { "x": { "type": "float32" } } },
+                        },
+                    },
+                    "empty": { "properties": {} },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions() {
+        assert_eq!(
+            r#"
+pub type Root = Option<Vec<User>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub name: Name,
+}
+
+pub type Name = String;
+"#
+            .trim_start(),
+            convert_serde(json!({
+                "definitions": {
+                    "name": { "type": "string" },
+                    "user": { "properties": { "name": { "ref": "name" } } },
+                },
+                "elements": { "ref": "user" },
+                "nullable": true,
+            }))
+        );
+
+        // A root that's only a ref, as with a root name, needs no type of its
+        // own.
+        assert_eq!(
+            r#"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Root {
+    #[serde(rename = "self")]
+    pub self_: bool,
+}
+"#
+            .trim_start(),
+            convert_serde(json!({
+                "definitions": {
+                    "Root": { "properties": { "self": { "type": "boolean" } } },
+                },
+                "ref": "Root",
+            }))
+        );
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!("FirstName", pascal_case("firstName"));
+        assert_eq!("FirstName", pascal_case("first_name"));
+        assert_eq!("first_name", snake_case("FirstName"));
+        assert_eq!("http_status2", snake_case("HTTP status2"));
+        assert_eq!("_", snake_case("?"));
+        assert_eq!("_2fa", snake_case("2fa"));
+        assert_eq!("gen_", snake_case("gen"));
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use jtd::Schema;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// The key in the root schema's `metadata` that a stamp is stored under.
+pub(crate) const STAMP_KEY: &str = "jtdInfer";
+
+/// Records, in the `metadata` of `schema`, which version of this crate
+/// generated it, from how many examples, and when.
+///
+/// Any metadata the schema already has is kept.
+pub(crate) fn stamp(schema: &mut Schema, samples: usize, generated_at: DateTime<Utc>) {
+    metadata_mut(schema).insert(
+        STAMP_KEY.to_owned(),
+        json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "samples": samples,
+            "generatedAt": generated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }),
+    );
+}
+
+pub(crate) fn metadata_mut(schema: &mut Schema) -> &mut BTreeMap<String, Value> {
+    match schema {
+        Schema::Empty { metadata, .. }
+        | Schema::Ref { metadata, .. }
+        | Schema::Type { metadata, .. }
+        | Schema::Enum { metadata, .. }
+        | Schema::Elements { metadata, .. }
+        | Schema::Properties { metadata, .. }
+        | Schema::Values { metadata, .. }
+        | Schema::Discriminator { metadata, .. } => metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn stamp_keeps_metadata() {
+        let mut metadata = BTreeMap::new();
+        metadata.insert("format".to_owned(), json!("date"));
+
+        let mut schema = Schema::Type {
+            definitions: Default::default(),
+            metadata,
+            nullable: false,
+            type_: jtd::Type::String,
+        };
+
+        stamp(
+            &mut schema,
+            3,
+            Utc.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap(),
+        );
+
+        assert_eq!(
+            json!({
+                "type": "string",
+                "metadata": {
+                    "format": "date",
+                    "jtdInfer": {
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "samples": 3,
+                        "generatedAt": "2020-01-02T03:04:05Z",
+                    },
+                },
+            }),
+            serde_json::to_value(schema.into_serde_schema()).unwrap()
+        );
+    }
+}
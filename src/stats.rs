@@ -0,0 +1,161 @@
+use crate::error::{from_json_pointer, to_json_pointer};
+use crate::hints::{HintSet, Hints, WILDCARD};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Statistics about the examples given to an [`Inferrer`][`crate::Inferrer`],
+/// for judging how representative they were.
+///
+/// These are only collected by an inferrer that's
+/// [tracking stats][`crate::Inferrer::tracking_stats`], and are returned by
+/// [`Inferrer::stats`][`crate::Inferrer::stats`]. They serialize to JSON in a
+/// stable form, with camelCase keys:
+///
+/// ```json
+/// {
+///   "samples": 2,
+///   "positions": {
+///     "": { "count": 2, "nulls": 0 },
+///     "/age": { "count": 2, "nulls": 1, "min": 42.0, "max": 42.0 }
+///   },
+///   "enumFallbacks": []
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// The number of examples inferred from.
+    pub samples: usize,
+
+    /// Statistics for each part of the input that any example had, keyed by
+    /// its path as a JSON Pointer.
+    ///
+    /// Array elements are all counted together under a `-` segment, and so
+    /// are the values of objects under a values hint.
+    pub positions: BTreeMap<String, PositionStats>,
+
+    /// The paths, as JSON Pointers, to the parts of the input that were to be
+    /// inferred as an enum, but that were inferred as a plain string or
+    /// number instead. This happens when an enum hint sees more values than
+    /// [`Hints::with_max_enum_size`] allows, or when the numbers at a position
+    /// under [`Hints::with_integer_enums`] didn't make for an enum.
+    pub enum_fallbacks: Vec<String>,
+}
+
+impl Stats {
+    /// Whether any example had a part that a hint at `path` would apply to.
+    /// `path` is in the same form as the paths given to [`Hints`], wildcards
+    /// and all, so a hint that this is false for went unused.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Hints, Inferrer};
+    ///
+    /// let stats = Inferrer::new(Hints::default())
+    ///     .tracking_stats()
+    ///     .infer(json!({ "a": [{ "b": 1 }] }))
+    ///     .stats()
+    ///     .unwrap();
+    ///
+    /// assert!(stats.reaches(&["a".to_owned(), "-".to_owned(), "b".to_owned()]));
+    /// assert!(!stats.reaches(&["b".to_owned()]));
+    /// ```
+    pub fn reaches(&self, path: &[String]) -> bool {
+        let hint_set = HintSet::new(vec![path]);
+        self.positions
+            .keys()
+            .filter_map(|pointer| from_json_pointer(pointer))
+            .any(|position| hint_set.matches(&position))
+    }
+}
+
+/// Statistics for one part of the input. See [`Stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PositionStats {
+    /// The number of values seen here, nulls included. For array elements,
+    /// this counts every element of every array.
+    pub count: usize,
+
+    /// How many of those values were null.
+    pub nulls: usize,
+
+    /// The smallest number seen here, if any were numbers other than NaN.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+
+    /// The largest number seen here, if any were numbers other than NaN.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// The running statistics of an [`Inferrer`][`crate::Inferrer`], before the
+/// enum fallbacks are worked out from the inference.
+#[derive(Debug, Default)]
+pub(crate) struct Tracker {
+    positions: BTreeMap<Vec<String>, PositionStats>,
+}
+
+impl Tracker {
+    /// Counts `value`, and everything within it, at `path`.
+    pub(crate) fn observe(&mut self, value: &Value, hints: &Hints, path: &mut Vec<String>) {
+        let stats = self.positions.entry(path.clone()).or_default();
+        stats.count += 1;
+
+        match value {
+            Value::Null => stats.nulls += 1,
+            // NaN has no place in a range of numbers, and so it's left out,
+            // as it is when inferring a number type.
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap() + 0.0;
+                if !n.is_nan() {
+                    stats.min = Some(stats.min.map_or(n, |min| min.min(n)));
+                    stats.max = Some(stats.max.map_or(n, |max| max.max(n)));
+                }
+            }
+            Value::Array(values) => {
+                let sub_hints = hints.sub_hints(WILDCARD);
+                path.push(WILDCARD.to_owned());
+                for value in values {
+                    self.observe(value, &sub_hints, path);
+                }
+                path.pop();
+            }
+            Value::Object(values) if hints.is_values_active() => {
+                let sub_hints = hints.values_sub_hints(WILDCARD);
+                path.push(WILDCARD.to_owned());
+                for value in values.values() {
+                    self.observe(value, &sub_hints, path);
+                }
+                path.pop();
+            }
+            Value::Object(values) => {
+                for (k, value) in values {
+                    path.push(k.clone());
+                    self.observe(value, &hints.sub_hints(k), path);
+                    path.pop();
+                }
+            }
+            Value::Bool(_) | Value::String(_) => {}
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.positions.clear();
+    }
+
+    pub(crate) fn to_stats(&self, samples: usize, enum_fallbacks: Vec<Vec<String>>) -> Stats {
+        Stats {
+            samples,
+            positions: self
+                .positions
+                .iter()
+                .map(|(path, stats)| (to_json_pointer(path), *stats))
+                .collect(),
+            enum_fallbacks: enum_fallbacks
+                .iter()
+                .map(|path| to_json_pointer(path))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::{to_json_pointer, type_name};
+use crate::inferred_number::NumType;
+use jtd::Type;
+use std::fmt;
+
+/// Something questionable about the examples given to an
+/// [`Inferrer`][`crate::Inferrer`], which doesn't stop it from inferring a
+/// schema.
+///
+/// See [`Inferrer::warnings`][`crate::Inferrer::warnings`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The examples at a part of the input were of kinds that no schema
+    /// besides the empty form accepts, such as numbers and objects. That part
+    /// of the input is inferred as the empty form.
+    ///
+    /// `path` is the path to that part of the input, in the same form as the
+    /// paths given to [`Hints`][`crate::Hints`]. `kinds` describes every kind
+    /// of example seen there, at least two, in sorted order. Neither the schema
+    /// nor the warning depends on the order the examples came in.
+    TypeConflict {
+        path: Vec<String>,
+        kinds: Vec<&'static str>,
+    },
+
+    /// An example had the same key more than once in one object. Only the last
+    /// value for the key was inferred from, and the others were ignored.
+    ///
+    /// `path` is the path to the key, ending in the key itself, in the same
+    /// form as above. Each path is only reported once. Repeated keys are only
+    /// found in examples given to
+    /// [`Inferrer::infer_checked`][`crate::Inferrer::infer_checked`].
+    DuplicateKey { path: Vec<String> },
+
+    /// The numbers at a part of the input included an integer too large for
+    /// an `i64` or a `u64`. It was inferred as `float64`, which can only
+    /// approximate it, and so generated code may lose some of its digits.
+    ///
+    /// `path` is the path to that part of the input, in the same form as
+    /// above. See [`BigIntegers`][`crate::BigIntegers`] for how to infer a
+    /// string instead, which doesn't give this warning.
+    BigInteger { path: Vec<String> },
+
+    /// The numbers at a part of the input included NaN or an infinity. JSON
+    /// has neither, so these can only come from a [`Value`][`serde_json::Value`]
+    /// that wasn't parsed from JSON. That part of the input was inferred as
+    /// `float64`, with infinities taken to be the largest `float64` of the
+    /// same sign, but no JSON Typedef type accepts such numbers as they were.
+    ///
+    /// `path` is the path to that part of the input, in the same form as
+    /// above. This takes the place of a [`Warning::NumberOutOfRange`] there.
+    NonFiniteNumber { path: Vec<String> },
+
+    /// The numbers at a part of the input didn't all fit in the number type
+    /// that's tried first there, either the default number type or one given
+    /// by a number type hint. They were inferred as the `inferred` type
+    /// instead.
+    ///
+    /// `path` is the path to that part of the input, in the same form as
+    /// above. `default` is the type that was tried first, and `min` and `max`
+    /// are the smallest and largest numbers seen there. Numbers that are given
+    /// a type hint aren't checked.
+    NumberOutOfRange {
+        path: Vec<String>,
+        default: NumType,
+        min: f64,
+        max: f64,
+        inferred: Type,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::TypeConflict { path, kinds } => {
+                // A warning made by hand may list fewer kinds than a real
+                // conflict would, so those still read sensibly.
+                let kinds = match kinds.split_last() {
+                    None => "of kinds that conflict".to_owned(),
+                    Some((only, [])) => format!("all {}", only),
+                    Some((last, [first])) => format!("both {} and {}", first, last),
+                    Some((last, rest)) => format!("a mix of {} and {}", rest.join(", "), last),
+                };
+
+                write!(
+                    f,
+                    "examples at {:?} were {}, so any value is accepted there",
+                    to_json_pointer(path),
+                    kinds
+                )
+            }
+            Warning::DuplicateKey { path } => write!(
+                f,
+                "the key at {:?} was repeated within an object, so only its last value was inferred from",
+                to_json_pointer(path)
+            ),
+            Warning::BigInteger { path } => write!(
+                f,
+                "numbers at {:?} included integers too large for 64 bits, which float64 can only approximate",
+                to_json_pointer(path)
+            ),
+            Warning::NonFiniteNumber { path } => write!(
+                f,
+                "numbers at {:?} included NaN or infinity, which JSON can't represent, so they were inferred as float64",
+                to_json_pointer(path)
+            ),
+            Warning::NumberOutOfRange {
+                path,
+                default,
+                min,
+                max,
+                inferred,
+            } => write!(
+                f,
+                "numbers at {:?} ranged from {} to {}, which don't all fit in {}, so they were inferred as {}",
+                to_json_pointer(path),
+                min,
+                max,
+                type_name(&default.into_type()),
+                type_name(inferred)
+            ),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::error;
+use crate::{HintSet, Hints, InferError, Inferrer, NumType, Warning};
+use serde::{Deserialize, Serialize};
+use serde_json::Deserializer;
+use serde_wasm_bindgen::from_value;
+use std::io::{BufReader, Cursor};
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaParams {
+    input: String,
+    enum_hints: Vec<String>,
+    values_hints: Vec<String>,
+    discriminator_hints: Vec<String>,
+    default_number_type: String,
+    #[serde(default)]
+    open_objects: bool,
+    #[serde(default)]
+    stamp: bool,
+    #[serde(default)]
+    array_as_samples: bool,
+}
+
+#[wasm_bindgen]
+pub fn generate_schema(params_js: JsValue) -> Result<String, JsValue> {
+    let (serde_schema, _) = infer_from_params(params_js)?;
+    serde_json::to_string(&serde_schema).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Like `generate_schema`, but returns an object with the inferred `schema`,
+/// and a list of `warnings` about the input. Each warning has the `path` it
+/// applies to, as a JSON Pointer, and a human-readable `message`. Type
+/// conflicts also have every kind of data that conflicted, in sorted order, as
+/// `kinds`. Numbers that didn't fit in the default number type also have it as
+/// `default`, the range of numbers seen as `min` and `max`, and the type that
+/// was `inferred` instead.
+#[wasm_bindgen]
+pub fn generate_schema_with_warnings(params_js: JsValue) -> Result<String, JsValue> {
+    let (serde_schema, warnings) = infer_from_params(params_js)?;
+
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|warning| match warning {
+            Warning::TypeConflict { path, kinds } => serde_json::json!({
+                "path": error::to_json_pointer(path),
+                "message": warning.to_string(),
+                "kinds": kinds,
+            }),
+            Warning::DuplicateKey { path }
+            | Warning::BigInteger { path }
+            | Warning::NonFiniteNumber { path } => serde_json::json!({
+                "path": error::to_json_pointer(path),
+                "message": warning.to_string(),
+            }),
+            Warning::NumberOutOfRange {
+                path,
+                default,
+                min,
+                max,
+                inferred,
+            } => serde_json::json!({
+                "path": error::to_json_pointer(path),
+                "message": warning.to_string(),
+                "default": default,
+                "min": min,
+                "max": max,
+                "inferred": error::type_name(inferred),
+            }),
+        })
+        .collect();
+
+    let result = serde_json::json!({ "schema": serde_schema, "warnings": warnings });
+    serde_json::to_string(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn infer_from_params(params_js: JsValue) -> Result<(jtd::SerdeSchema, Vec<Warning>), JsValue> {
+    //     let params: SchemaParams = params_js.into_serde().map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let params: SchemaParams =
+        from_value(params_js).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    //     let enum_hints: Vec<String> = serde_json::from_str(&enum_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    //     let values_hints: Vec<String> = serde_json::from_str(&values_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    //     let discriminator_hints: Vec<String> = serde_json::from_str(&discriminator_hints.as_string().unwrap()).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let reader = BufReader::new(Cursor::new(params.input));
+
+    let enum_hints: Vec<Vec<_>> = params
+        .enum_hints
+        .iter()
+        .map(|hint| crate::parse_json_pointer(hint))
+        .collect();
+
+    let values_hints: Vec<Vec<_>> = params
+        .values_hints
+        .iter()
+        .map(|hint| crate::parse_json_pointer(hint))
+        .collect();
+
+    let discriminator_hints: Vec<Vec<_>> = params
+        .discriminator_hints
+        .iter()
+        .map(|hint| crate::parse_json_pointer(hint))
+        .collect();
+
+    let default_num_type: NumType = params
+        .default_number_type
+        .parse()
+        .map_err(|e: InferError| JsValue::from_str(&e.to_string()))?;
+
+    let hints = Hints::new(
+        default_num_type,
+        HintSet::from_paths(&enum_hints),
+        HintSet::from_paths(&values_hints),
+        HintSet::from_paths(&discriminator_hints),
+    )
+    .with_open_objects(params.open_objects);
+
+    let inferrer = if params.array_as_samples {
+        let mut inferrer = Inferrer::new(hints);
+        for value in Deserializer::from_reader(reader).into_iter() {
+            inferrer =
+                inferrer.infer_elements(value.map_err(|e| JsValue::from_str(&e.to_string()))?);
+        }
+        inferrer
+    } else {
+        Inferrer::new(hints)
+            .infer_json_stream(reader)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?
+    };
+
+    let warnings = inferrer.warnings();
+    let schema = if params.stamp {
+        inferrer.into_stamped_schema()
+    } else {
+        inferrer.into_schema()
+    }
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok((schema.into_serde_schema(), warnings))
+}